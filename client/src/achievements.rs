@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState,
+    attract::AttractMode,
+    components::{AchievementsMenu, MainMenu, Toast},
+    events::{EnemyKilled, PlayerHit, WaveStarted},
+    get_data_path,
+    save::{load_json, save_json},
+    stats::Stats,
+};
+
+const LIFETIME_KILLS_GOAL: u64 = 100;
+const WAVE_GOAL: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstKill,
+    HundredKills,
+    NoHitWave,
+    ReachWave10,
+}
+
+impl Achievement {
+    const ALL: [Achievement; 4] = [
+        Achievement::FirstKill,
+        Achievement::HundredKills,
+        Achievement::NoHitWave,
+        Achievement::ReachWave10,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstKill => "First Kill",
+            Achievement::HundredKills => "100 Kills",
+            Achievement::NoHitWave => "No-Hit Wave",
+            Achievement::ReachWave10 => "Reach Wave 10",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstKill => "Destroy your first enemy",
+            Achievement::HundredKills => "Destroy 100 enemies across all runs",
+            Achievement::NoHitWave => "Clear a wave without getting hit",
+            Achievement::ReachWave10 => "Reach wave 10 in a single run",
+        }
+    }
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    unlocked: Vec<Achievement>,
+    #[serde(skip)]
+    hit_this_wave: bool,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    /// Unlocks an achievement, returning `true` if it was newly unlocked.
+    fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(achievement) {
+            return false;
+        }
+        self.unlocked.push(achievement);
+        true
+    }
+}
+
+#[derive(Resource, Deref)]
+struct AchievementsPath(PathBuf);
+
+pub struct AchievementsPlugin;
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        let achievements_path = get_data_path("achievements.json").unwrap_or_default();
+        let achievements: Achievements = load_json(&achievements_path);
+
+        app.insert_resource(achievements)
+            .insert_resource(AchievementsPath(achievements_path))
+            .add_systems(Update, unlock_achievements)
+            .add_systems(Update, toast_fade)
+            .add_systems(
+                Update,
+                toggle_achievements_menu.run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn unlock_achievements(
+    mut commands: Commands,
+    mut achievements: ResMut<Achievements>,
+    achievements_path: Res<AchievementsPath>,
+    stats: Res<Stats>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut player_hit: EventReader<PlayerHit>,
+    mut wave_started: EventReader<WaveStarted>,
+    attract: Res<AttractMode>,
+) {
+    // the demo doesn't earn anything
+    if **attract {
+        enemy_killed.clear();
+        player_hit.clear();
+        wave_started.clear();
+        return;
+    }
+
+    let mut newly_unlocked = Vec::new();
+
+    if enemy_killed.read().count() > 0 {
+        newly_unlocked.push(Achievement::FirstKill);
+    }
+    if stats.enemies_destroyed >= LIFETIME_KILLS_GOAL {
+        newly_unlocked.push(Achievement::HundredKills);
+    }
+    if player_hit.read().count() > 0 {
+        achievements.hit_this_wave = true;
+    }
+    for WaveStarted(wave) in wave_started.read() {
+        if *wave > 1 && !achievements.hit_this_wave {
+            newly_unlocked.push(Achievement::NoHitWave);
+        }
+        if *wave >= WAVE_GOAL {
+            newly_unlocked.push(Achievement::ReachWave10);
+        }
+        achievements.hit_this_wave = false;
+    }
+
+    let mut changed = false;
+    for achievement in newly_unlocked {
+        if achievements.unlock(achievement) {
+            changed = true;
+            commands.spawn((
+                Text::new(format!("Achievement unlocked: {}", achievement.name())),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(40.0),
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                Toast::default(),
+            ));
+        }
+    }
+
+    if changed {
+        let _ = save_json(&achievements_path, &*achievements);
+    }
+}
+
+fn toast_fade(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Toast, &mut TextColor)>,
+) {
+    for (entity, mut toast, mut color) in &mut query {
+        toast.0.tick(time.delta());
+        color.0.set_alpha(toast.0.fraction_remaining());
+        if toast.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn toggle_achievements_menu(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    achievements: Res<Achievements>,
+    menu_query: Query<Entity, With<AchievementsMenu>>,
+) {
+    if !input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    if let Ok(entity) = menu_query.single() {
+        commands.entity(entity).despawn();
+        return;
+    }
+
+    let list = Achievement::ALL
+        .iter()
+        .map(|achievement| {
+            let mark = if achievements.is_unlocked(*achievement) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            format!(
+                "{} {} - {}",
+                mark,
+                achievement.name(),
+                achievement.description()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    commands.spawn((
+        Text::new(format!("Achievements\n\n{}", list)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            left: Val::Px(100.0),
+            ..default()
+        },
+        AchievementsMenu,
+        MainMenu,
+    ));
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashSet, time::Duration};
+
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+    time::common_conditions::on_timer,
+};
+use rand::Rng;
+
+use crate::{
+    GameRng, GameState, PlayerDamage, Score, WinSize,
+    components::{
+        Asteroid, FromPlayer, Hitbox, Invulnerable, Laser, Movable, Player, SpriteSize, Velocity,
+    },
+    events::SpawnExplosion,
+    hitbox_half_size,
+    pause::Pause,
+};
+
+const ASTEROID_SPAWN_SECONDS: f64 = 4.0;
+const ASTEROID_SPAWN_CHANCE: f64 = 0.5;
+const ASTEROID_LARGE_SIZE: f32 = 60.0;
+const ASTEROID_SMALL_SIZE: f32 = 28.0;
+/// Player hits a large asteroid takes before splitting.
+const ASTEROID_LARGE_HITS: u32 = 3;
+const ASTEROID_FALL_SPEED: f32 = 0.15;
+const ASTEROID_SPLIT_SPEED: f32 = 0.12;
+const ASTEROID_LARGE_SCORE: u32 = 2;
+const ASTEROID_SMALL_SCORE: u32 = 1;
+
+pub struct AsteroidPlugin;
+impl Plugin for AsteroidPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), asteroids_clear)
+            .add_systems(
+                Update,
+                asteroid_spawn
+                    .run_if(on_timer(Duration::from_secs_f64(ASTEROID_SPAWN_SECONDS)))
+                    .run_if(in_state(Pause::Running)),
+            )
+            .add_systems(
+                Update,
+                (asteroid_hit_laser, asteroid_hit_player).run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+fn asteroids_clear(mut commands: Commands, query: Query<Entity, With<Asteroid>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_asteroid(commands: &mut Commands, position: Vec2, velocity: Vec2, large: bool) {
+    let (size, hits) = if large {
+        (ASTEROID_LARGE_SIZE, ASTEROID_LARGE_HITS)
+    } else {
+        (ASTEROID_SMALL_SIZE, 1)
+    };
+    commands.spawn((
+        Sprite::from_color(Color::srgb(0.45, 0.4, 0.35), Vec2::splat(size)),
+        Transform::from_translation(position.extend(8.0)),
+        SpriteSize(Vec2::splat(size)),
+        Velocity {
+            x: velocity.x,
+            y: velocity.y,
+        },
+        Movable { auto_despawn: true },
+        Asteroid {
+            large,
+            hits_left: hits,
+        },
+    ));
+}
+
+fn asteroid_spawn(mut commands: Commands, win_size: Res<WinSize>, mut rng: ResMut<GameRng>) {
+    if !rng.random_bool(ASTEROID_SPAWN_CHANCE) {
+        return;
+    }
+    let w_span = win_size.w / 2.0 - ASTEROID_LARGE_SIZE;
+    let position = Vec2::new(
+        rng.random_range(-w_span..w_span),
+        win_size.h / 2.0 + ASTEROID_LARGE_SIZE,
+    );
+    let velocity = Vec2::new(rng.random_range(-0.05..0.05), -ASTEROID_FALL_SPEED);
+    spawn_asteroid(&mut commands, position, velocity, rng.random_bool(0.5));
+}
+
+/// Asteroids soak up lasers from both sides; only the player's wear them down.
+fn asteroid_hit_laser(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    laser_query: Query<(Entity, &Transform, &SpriteSize, Has<FromPlayer>), With<Laser>>,
+    mut asteroid_query: Query<(Entity, &Transform, &SpriteSize, &Velocity, &mut Asteroid)>,
+) {
+    let mut despawned_entities: HashSet<Entity> = HashSet::new();
+
+    for (asteroid_entity, asteroid_tf, asteroid_size, velocity, mut asteroid) in &mut asteroid_query
+    {
+        let asteroid_box = Aabb2d::new(
+            asteroid_tf.translation.truncate(),
+            asteroid_size.half_extents(asteroid_tf.scale.truncate()),
+        );
+
+        for (laser_entity, laser_tf, laser_size, from_player) in &laser_query {
+            if despawned_entities.contains(&laser_entity) || asteroid.hits_left == 0 {
+                continue;
+            }
+            let laser_box = Aabb2d::new(
+                laser_tf.translation.truncate(),
+                laser_size.half_extents(laser_tf.scale.truncate()),
+            );
+            if !laser_box.intersects(&asteroid_box) {
+                continue;
+            }
+
+            despawned_entities.insert(laser_entity);
+            commands.entity(laser_entity).despawn();
+            if from_player {
+                asteroid.hits_left -= 1;
+            }
+        }
+
+        if asteroid.hits_left > 0 {
+            continue;
+        }
+        commands.entity(asteroid_entity).despawn();
+        spawn_explosion.write(SpawnExplosion::medium(asteroid_tf.translation));
+
+        if asteroid.large {
+            **score += ASTEROID_LARGE_SCORE;
+            // split into two smaller rocks drifting apart
+            let position = asteroid_tf.translation.truncate();
+            for side in [-1.0, 1.0] {
+                let split_velocity =
+                    Vec2::new(velocity.x + side * ASTEROID_SPLIT_SPEED, velocity.y);
+                spawn_asteroid(&mut commands, position, split_velocity, false);
+            }
+        } else {
+            **score += ASTEROID_SMALL_SCORE;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn asteroid_hit_player(
+    mut commands: Commands,
+    player_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&Hitbox>),
+        (With<Player>, Without<Invulnerable>),
+    >,
+    asteroid_query: Query<(Entity, &Transform, &SpriteSize), With<Asteroid>>,
+    mut damage: PlayerDamage,
+) {
+    let Ok((player_entity, player_tf, player_size, hitbox)) = player_query.single() else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_tf.translation.truncate(),
+        hitbox_half_size(player_size, player_tf, hitbox),
+    );
+
+    for (asteroid_entity, asteroid_tf, asteroid_size) in &asteroid_query {
+        let asteroid_box = Aabb2d::new(
+            asteroid_tf.translation.truncate(),
+            asteroid_size.half_extents(asteroid_tf.scale.truncate()),
+        );
+        if player_box.intersects(&asteroid_box) {
+            commands.entity(asteroid_entity).despawn();
+            damage.hit(player_entity, player_tf);
+            break;
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    GameState, RunReset, WinSize,
+    components::{Asteroid, Enemy, FromEnemy, Laser, MainMenu, Player},
+    input::{KeyBindings, PlayerActions, read_mouse_actions},
+    main_menu,
+    targeting::nearest_enemy,
+};
+
+/// Seconds of no input on the title screen before the game starts playing itself.
+const ATTRACT_IDLE_SECONDS: f32 = 10.0;
+/// Enemy lasers within this height above the ship are treated as incoming.
+const ATTRACT_LOOKAHEAD: f32 = 300.0;
+/// Horizontal distance the pilot keeps from an incoming laser's path.
+const ATTRACT_LASER_CLEARANCE: f32 = 50.0;
+/// Positions across the screen the pilot considers each frame.
+const ATTRACT_SAMPLES: u32 = 24;
+/// Close enough to the chosen position to stop moving.
+const ATTRACT_DEADZONE: f32 = 8.0;
+
+/// Set while the title screen runs a self-playing demo behind the menu.
+#[derive(Resource, Deref, DerefMut, PartialEq)]
+pub struct AttractMode(pub bool);
+
+#[derive(Resource, Deref, DerefMut)]
+struct AttractIdle(Timer);
+
+impl Default for AttractIdle {
+    fn default() -> Self {
+        Self(Timer::from_seconds(ATTRACT_IDLE_SECONDS, TimerMode::Once))
+    }
+}
+
+pub struct AttractPlugin;
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AttractMode(false))
+            .init_resource::<AttractIdle>()
+            .add_systems(OnEnter(GameState::MainMenu), attract_idle_reset)
+            .add_systems(Update, attract_start.run_if(in_state(GameState::MainMenu)))
+            // stands in for the devices, so it overwrites whatever they read
+            .add_systems(
+                PreUpdate,
+                attract_pilot
+                    .after(InputSystem)
+                    .after(read_mouse_actions)
+                    .run_if(resource_equals(AttractMode(true))),
+            )
+            // after gameplay, so a death this frame can be caught before the results
+            // screen shows
+            .add_systems(
+                PostUpdate,
+                attract_exit.run_if(resource_equals(AttractMode(true))),
+            );
+    }
+}
+
+/// Whether anything was pressed this frame, on any device.
+fn any_input(
+    keyboard: &ButtonInput<KeyCode>,
+    mouse: &ButtonInput<MouseButton>,
+    gamepads: &Query<&Gamepad>,
+) -> bool {
+    keyboard.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+}
+
+fn attract_idle_reset(mut idle: ResMut<AttractIdle>) {
+    idle.reset();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn attract_start(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut idle: ResMut<AttractIdle>,
+    mut attract: ResMut<AttractMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut run: RunReset,
+) {
+    let moved = cursor_moved.read().count() > 0;
+    if moved || any_input(&keyboard, &mouse, &gamepads) {
+        idle.reset();
+        return;
+    }
+    if !idle.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // the menu stays up on top of the demo
+    run.restart_run();
+    **attract = true;
+    next_state.set(GameState::Playing);
+}
+
+/// Lines up under the nearest enemy and keeps firing, sidestepping to whichever
+/// position is clear of incoming lasers.
+fn attract_pilot(
+    mut actions: ResMut<PlayerActions>,
+    win_size: Res<WinSize>,
+    player_query: Query<&Transform, With<Player>>,
+    laser_query: Query<&Transform, (With<Laser>, With<FromEnemy>)>,
+    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+) {
+    *actions = PlayerActions::default();
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    let player = player_tf.translation.truncate();
+
+    let incoming: Vec<f32> = laser_query
+        .iter()
+        .map(|tf| tf.translation.truncate())
+        .filter(|laser| laser.y > player.y && laser.y - player.y < ATTRACT_LOOKAHEAD)
+        .map(|laser| laser.x)
+        .collect();
+    let goal = nearest_enemy(
+        player,
+        enemy_query
+            .iter()
+            .map(|(entity, tf)| (entity, tf.translation.truncate())),
+    )
+    .and_then(|entity| enemy_query.get(entity).ok())
+    .map_or(0.0, |(_, tf)| tf.translation.x);
+
+    // dodging always wins over lining up a shot
+    let cost = |x: f32| {
+        let danger = incoming
+            .iter()
+            .filter(|laser_x| (x - **laser_x).abs() < ATTRACT_LASER_CLEARANCE)
+            .count();
+        danger as f32 * win_size.w + (x - goal).abs()
+    };
+    let half_span = win_size.w / 2.0 - ATTRACT_LASER_CLEARANCE;
+    let target = (0..=ATTRACT_SAMPLES)
+        .map(|i| -half_span + 2.0 * half_span * i as f32 / ATTRACT_SAMPLES as f32)
+        .min_by(|a, b| cost(*a).total_cmp(&cost(*b)))
+        .unwrap_or(player.x);
+
+    let offset = target - player.x;
+    if offset.abs() > ATTRACT_DEADZONE {
+        actions.move_x = offset.signum();
+    }
+    actions.fire = true;
+}
+
+/// Back to the title screen on any press, or when the demo ship runs out of lives.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn attract_exit(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<KeyBindings>,
+    mut attract: ResMut<AttractMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+    menu_query: Query<Entity, With<MainMenu>>,
+    leftover_query: Query<Entity, Or<(With<Enemy>, With<Laser>, With<Asteroid>)>>,
+) {
+    let died = matches!(*next_state, NextState::Pending(GameState::GameOver));
+    if !died && !any_input(&keyboard, &mouse, &gamepads) {
+        return;
+    }
+
+    **attract = false;
+    // the title screen spawns its panels again on entry
+    for entity in menu_query.iter().chain(&leftover_query) {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn(main_menu(&bindings));
+    next_state.set(GameState::MainMenu);
+}
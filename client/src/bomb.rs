@@ -0,0 +1,120 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{
+    GameRng, GameState, Sandbox, Score,
+    components::{BombPickup, Enemy, Movable, Player, SpriteSize, Velocity},
+    events::{EnemyKilled, SpawnExplosion},
+    input::PlayerActions,
+    mutators::Mutators,
+    pause::Pause,
+};
+
+const BOMB_CHARGES: u32 = 2;
+const BOMB_MAX_CHARGES: u32 = 5;
+/// Chance that a destroyed enemy leaves a bomb pickup behind.
+const BOMB_DROP_CHANCE: f64 = 0.04;
+const BOMB_PICKUP_SIZE: f32 = 16.0;
+
+/// Screen-clearing bombs the player can still use this run.
+#[derive(Resource, Deref, DerefMut)]
+pub struct Bombs(pub u32);
+
+pub struct BombPlugin;
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Bombs(BOMB_CHARGES))
+            .add_systems(OnEnter(GameState::Playing), bombs_reset)
+            .add_systems(
+                Update,
+                (bomb_detonate, bomb_drop, bomb_pickup).run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+fn bombs_reset(
+    mut commands: Commands,
+    mut bombs: ResMut<Bombs>,
+    pickup_query: Query<Entity, With<BombPickup>>,
+) {
+    **bombs = BOMB_CHARGES;
+    for entity in &pickup_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn bomb_detonate(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    sandbox: Res<Sandbox>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    mut bombs: ResMut<Bombs>,
+    mut score: ResMut<Score>,
+    mutators: Res<Mutators>,
+    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+) {
+    if !actions.bomb || (**bombs == 0 && !**sandbox) {
+        return;
+    }
+    if !**sandbox {
+        **bombs -= 1;
+    }
+
+    for (entity, transform) in &enemy_query {
+        commands.entity(entity).despawn();
+        spawn_explosion.write(SpawnExplosion::small(transform.translation));
+        **score += mutators.score_multiplier();
+        enemy_killed.write(EnemyKilled(transform.translation));
+    }
+}
+
+fn bomb_drop(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+) {
+    for EnemyKilled(position) in enemy_killed.read() {
+        if !rng.random_bool(BOMB_DROP_CHANCE) {
+            continue;
+        }
+        commands.spawn((
+            Sprite::from_color(Color::srgb(1.0, 0.6, 0.1), Vec2::splat(BOMB_PICKUP_SIZE)),
+            Transform::from_xyz(position.x, position.y, 5.0),
+            SpriteSize(Vec2::splat(BOMB_PICKUP_SIZE)),
+            Velocity { x: 0.0, y: -0.2 },
+            Movable { auto_despawn: true },
+            BombPickup,
+        ));
+    }
+}
+
+fn bomb_pickup(
+    mut commands: Commands,
+    mut bombs: ResMut<Bombs>,
+    player_query: Query<(&Transform, &SpriteSize), With<Player>>,
+    pickup_query: Query<(Entity, &Transform, &SpriteSize), With<BombPickup>>,
+) {
+    let Ok((player_tf, player_size)) = player_query.single() else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_tf.translation.truncate(),
+        player_size.half_extents(player_tf.scale.truncate()),
+    );
+
+    for (entity, pickup_tf, pickup_size) in &pickup_query {
+        let pickup_box = Aabb2d::new(
+            pickup_tf.translation.truncate(),
+            pickup_size.half_extents(pickup_tf.scale.truncate()),
+        );
+        if player_box.intersects(&pickup_box) {
+            commands.entity(entity).despawn();
+            **bombs = (**bombs + 1).min(BOMB_MAX_CHARGES);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    GameRng, GameState, GameTextures, Score, WinSize,
+    camera::ScreenShake,
+    components::{Boss, BossWarning, Enemy, Exposed, FromPlayer, Hitbox, Laser, SpriteSize, Toast},
+    config::GameConfig,
+    difficulty::Difficulty,
+    enemy::{EnemyKind, spawn_enemy},
+    events::{EnemyKilled, SpawnExplosion, WaveStarted},
+    hitbox_half_size,
+    pause::Pause,
+    settings::Palette,
+};
+
+/// A boss arrives at the start of every wave that is a multiple of this.
+const BOSS_WAVE_INTERVAL: u32 = 5;
+const BOSS_WARNING_SECONDS: f32 = 2.0;
+const BOSS_WARNING_BLINK: f32 = 0.25;
+/// Quiet spell after a boss goes down before regular enemies return.
+const BOSS_DEFEATED_SECONDS: f32 = 2.0;
+const BOSS_SCORE: u32 = 50;
+/// Seconds and intensity of the shake when the boss goes down.
+const BOSS_DEATH_SHAKE: (f32, f32) = (0.6, 16.0);
+
+/// Where the run is in a boss encounter. Regular enemy spawning waits for `Absent`.
+///
+/// The game has no audio yet; the siren and boss music belong on the `Warning` and
+/// `Active` transitions once it does.
+#[derive(Resource, Default, PartialEq)]
+pub enum BossState {
+    #[default]
+    Absent,
+    /// The banner is up and the boss arrives when the timer runs out.
+    Warning(Timer),
+    Active,
+    /// The boss is down; spawning resumes when the timer runs out.
+    Defeated(Timer),
+}
+
+pub struct BossPlugin;
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BossState>()
+            .add_systems(OnEnter(GameState::Playing), boss_reset)
+            // the banner can't outlive the run, whatever ended it
+            .add_systems(OnExit(GameState::Playing), boss_warning_despawn)
+            .add_systems(
+                Update,
+                (boss_trigger, boss_advance, boss_warning_blink, boss_hit)
+                    .chain()
+                    .run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+fn boss_reset(
+    mut commands: Commands,
+    mut boss: ResMut<BossState>,
+    warning_query: Query<Entity, With<BossWarning>>,
+) {
+    *boss = BossState::Absent;
+    for entity in &warning_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn boss_warning_despawn(mut commands: Commands, warning_query: Query<Entity, With<BossWarning>>) {
+    for entity in &warning_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Puts the warning up when a boss wave starts.
+fn boss_trigger(
+    mut commands: Commands,
+    mut boss: ResMut<BossState>,
+    mut wave_started: EventReader<WaveStarted>,
+) {
+    let boss_wave = wave_started
+        .read()
+        .any(|WaveStarted(number)| number % BOSS_WAVE_INTERVAL == 0);
+    if !boss_wave || *boss != BossState::Absent {
+        return;
+    }
+
+    *boss = BossState::Warning(Timer::from_seconds(BOSS_WARNING_SECONDS, TimerMode::Once));
+    commands.spawn((
+        Text::new("WARNING"),
+        TextFont::from_font_size(72.0),
+        TextColor(Color::srgb(1.0, 0.2, 0.2)),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.6, 0.0, 0.0, 0.25)),
+        BossWarning,
+    ));
+}
+
+/// Moves the encounter along: the boss arrives after the warning, and regular
+/// spawning comes back a little after it dies, however it died.
+#[allow(clippy::too_many_arguments)]
+fn boss_advance(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut boss: ResMut<BossState>,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    difficulty: Res<Difficulty>,
+    mut rng: ResMut<GameRng>,
+    win_size: Res<WinSize>,
+    boss_query: Query<(), With<Boss>>,
+    warning_query: Query<Entity, With<BossWarning>>,
+) {
+    match &mut *boss {
+        BossState::Absent => {}
+        BossState::Warning(timer) => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            for entity in &warning_query {
+                commands.entity(entity).despawn();
+            }
+            spawn_enemy(
+                &mut commands,
+                &game_textures,
+                &config,
+                *palette,
+                *difficulty,
+                &mut rng,
+                EnemyKind::Boss,
+                Vec2::new(0.0, win_size.h / 2.0 - 150.0),
+            );
+            *boss = BossState::Active;
+        }
+        BossState::Active => {
+            if boss_query.is_empty() {
+                *boss = BossState::Defeated(Timer::from_seconds(
+                    BOSS_DEFEATED_SECONDS,
+                    TimerMode::Once,
+                ));
+                commands.spawn((
+                    Text::new("Boss Defeated!"),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(30.0),
+                        width: Val::Percent(100.0),
+                        ..default()
+                    },
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    Toast::default(),
+                ));
+            }
+        }
+        BossState::Defeated(timer) => {
+            if timer.tick(time.delta()).finished() {
+                *boss = BossState::Absent;
+            }
+        }
+    }
+}
+
+fn boss_warning_blink(
+    boss: Res<BossState>,
+    mut warning_query: Query<&mut Visibility, With<BossWarning>>,
+) {
+    let BossState::Warning(timer) = &*boss else {
+        return;
+    };
+    let on = ((timer.elapsed_secs() / BOSS_WARNING_BLINK) as u32).is_multiple_of(2);
+    for mut visibility in &mut warning_query {
+        visibility.set_if_neq(if on {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// Player lasers wear the boss down one hit at a time instead of destroying it.
+#[allow(clippy::type_complexity)]
+fn boss_hit(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
+    mut boss_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&Hitbox>, &mut Boss),
+        (With<Enemy>, Exposed),
+    >,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut screen_shake: ResMut<ScreenShake>,
+) {
+    for (boss_entity, boss_tf, boss_size, hitbox, mut boss) in &mut boss_query {
+        let boss_box = Aabb2d::new(
+            boss_tf.translation.truncate(),
+            hitbox_half_size(boss_size, boss_tf, hitbox),
+        );
+        for (laser_entity, laser_tf, laser_size) in &laser_query {
+            if boss.health == 0 {
+                break;
+            }
+            let laser_box = Aabb2d::new(
+                laser_tf.translation.truncate(),
+                laser_size.half_extents(laser_tf.scale.truncate()),
+            );
+            if laser_box.intersects(&boss_box) {
+                commands.entity(laser_entity).despawn();
+                boss.health -= 1;
+            }
+        }
+
+        if boss.health > 0 {
+            continue;
+        }
+        commands.entity(boss_entity).despawn();
+        spawn_explosion.write(SpawnExplosion::large(boss_tf.translation));
+        screen_shake.start(BOSS_DEATH_SHAKE.0, BOSS_DEATH_SHAKE.1);
+        **score += BOSS_SCORE;
+        enemy_killed.write(EnemyKilled(boss_tf.translation));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{ReduceEffects, components::MainCamera};
+
+/// Offsets the gameplay camera by a decaying random amount while the timer runs.
+#[derive(Resource)]
+pub struct ScreenShake {
+    pub timer: Timer,
+    pub intensity: f32,
+}
+
+impl Default for ScreenShake {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+            intensity: 0.0,
+        }
+    }
+}
+
+impl ScreenShake {
+    pub fn start(&mut self, seconds: f32, intensity: f32) {
+        self.timer = Timer::from_seconds(seconds, TimerMode::Once);
+        self.intensity = intensity;
+    }
+}
+
+pub struct CameraPlugin;
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenShake>()
+            .add_systems(Update, screen_shake);
+    }
+}
+
+fn screen_shake(
+    time: Res<Time>,
+    reduce_effects: Res<ReduceEffects>,
+    mut shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+) {
+    let Ok(mut transform) = camera_query.single_mut() else {
+        return;
+    };
+
+    shake.timer.tick(time.delta());
+    if shake.timer.finished() || **reduce_effects {
+        // settle back exactly on the origin so the view never drifts
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        return;
+    }
+
+    let strength = shake.intensity * shake.timer.fraction_remaining();
+    let mut rng = rand::rng();
+    transform.translation.x = rng.random_range(-1.0..=1.0) * strength;
+    transform.translation.y = rng.random_range(-1.0..=1.0) * strength;
+}
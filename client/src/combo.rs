@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameState,
+    components::{ComboUI, StreakAnnouncement, Toast},
+    events::{EnemyKilled, PlayerHit},
+};
+
+/// Seconds after a kill in which the next kill extends the combo.
+const COMBO_WINDOW: f32 = 1.5;
+/// Every this many combo kills grants a combo shield.
+const COMBO_SHIELD_KILLS: u32 = 10;
+/// Combo kills needed for each step of the score multiplier.
+const COMBO_MULTIPLIER_STEP: u32 = 5;
+const COMBO_MAX_MULTIPLIER: u32 = 5;
+const COMBO_FLASH_SECONDS: f32 = 0.3;
+const COMBO_FLASH_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+/// Combo counts that get called out, lowest first, with what is shown for each.
+const STREAK_TIERS: [(u32, &str); 5] = [
+    (2, "Double Kill"),
+    (3, "Triple Kill"),
+    (5, "Rampage"),
+    (8, "Unstoppable"),
+    (12, "Godlike"),
+];
+const STREAK_SECONDS: f32 = 1.2;
+const STREAK_FONT_SIZE: f32 = 56.0;
+const STREAK_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+
+#[derive(Resource)]
+pub struct ComboState {
+    pub count: u32,
+    pub timer: Timer,
+}
+
+impl ComboState {
+    /// Points awarded per kill at the current combo.
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.count / COMBO_MULTIPLIER_STEP).min(COMBO_MAX_MULTIPLIER)
+    }
+}
+
+impl Default for ComboState {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            timer: Timer::from_seconds(COMBO_WINDOW, TimerMode::Once),
+        }
+    }
+}
+
+/// Absorbs the next combo break, then is used up.
+#[derive(Resource, Deref, DerefMut, Default)]
+pub struct ComboShield(bool);
+
+pub struct ComboPlugin;
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ComboState>()
+            .init_resource::<ComboShield>()
+            .add_systems(Startup, combo_ui_spawn)
+            .add_systems(OnEnter(GameState::Playing), combo_reset)
+            .add_systems(
+                Update,
+                (combo_track, update_combo_ui, streak_announce)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), combo_ui_clear);
+    }
+}
+
+fn combo_ui_spawn(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(30.0),
+            left: Val::Px(5.0),
+            ..default()
+        },
+        ComboUI,
+    ));
+}
+
+fn combo_reset(mut combo: ResMut<ComboState>, mut shield: ResMut<ComboShield>) {
+    *combo = ComboState::default();
+    **shield = false;
+}
+
+/// Ends the combo unless a shield is up, in which case the shield is spent and the
+/// combo gets a fresh window.
+fn break_combo(combo: &mut ComboState, shield: &mut ComboShield) {
+    if **shield {
+        **shield = false;
+        combo.timer.reset();
+    } else {
+        combo.count = 0;
+    }
+}
+
+fn combo_track(
+    time: Res<Time>,
+    mut combo: ResMut<ComboState>,
+    mut shield: ResMut<ComboShield>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut player_hit: EventReader<PlayerHit>,
+) {
+    if combo.count > 0 && combo.timer.tick(time.delta()).just_finished() {
+        break_combo(&mut combo, &mut shield);
+    }
+    if combo.count > 0 && player_hit.read().count() > 0 {
+        break_combo(&mut combo, &mut shield);
+    }
+
+    for _ in enemy_killed.read() {
+        combo.count += 1;
+        combo.timer.reset();
+        if combo.count.is_multiple_of(COMBO_SHIELD_KILLS) {
+            **shield = true;
+        }
+    }
+}
+
+fn update_combo_ui(
+    time: Res<Time>,
+    combo: Res<ComboState>,
+    shield: Res<ComboShield>,
+    mut last_multiplier: Local<u32>,
+    mut flash: Local<f32>,
+    mut query: Query<(&mut Text, &mut TextColor), With<ComboUI>>,
+) {
+    let multiplier = combo.multiplier();
+    if multiplier > *last_multiplier {
+        *flash = COMBO_FLASH_SECONDS;
+    }
+    *last_multiplier = multiplier;
+    *flash = (*flash - time.delta_secs()).max(0.0);
+
+    for (mut text, mut color) in &mut query {
+        color.0 = Color::WHITE.mix(&COMBO_FLASH_COLOR, *flash / COMBO_FLASH_SECONDS);
+        if !combo.is_changed() && !shield.is_changed() {
+            continue;
+        }
+        let mut label = if combo.count > 1 {
+            format!("Combo: {}  x{}", combo.count, multiplier)
+        } else {
+            String::new()
+        };
+        if **shield {
+            label.push_str("  [shield]");
+        }
+        text.0 = label.trim_start().to_string();
+    }
+}
+
+/// Calls out the highest streak tier the combo climbed past since last frame, in
+/// place of any callout still fading.
+fn streak_announce(
+    mut commands: Commands,
+    combo: Res<ComboState>,
+    mut last_count: Local<u32>,
+    announcement_query: Query<Entity, With<StreakAnnouncement>>,
+) {
+    let previous = std::mem::replace(&mut *last_count, combo.count);
+    let Some((_, label)) = STREAK_TIERS
+        .iter()
+        .rev()
+        .find(|(kills, _)| previous < *kills && combo.count >= *kills)
+    else {
+        return;
+    };
+
+    for entity in &announcement_query {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        Text::new(*label),
+        TextFont::from_font_size(STREAK_FONT_SIZE),
+        TextColor(STREAK_COLOR),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(40.0),
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        Toast(Timer::from_seconds(STREAK_SECONDS, TimerMode::Once)),
+        StreakAnnouncement,
+    ));
+}
+
+fn combo_ui_clear(
+    mut commands: Commands,
+    mut query: Query<&mut Text, With<ComboUI>>,
+    announcement_query: Query<Entity, With<StreakAnnouncement>>,
+) {
+    for mut text in &mut query {
+        text.0.clear();
+    }
+    for entity in &announcement_query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameState, GameTextures,
+    combo::ComboState,
+    components::{Companion, Enemy, Player},
+    config::GameConfig,
+    pause::Pause,
+    player::spawn_player_laser,
+    settings::Palette,
+    targeting::nearest_enemy,
+};
+
+/// Where the companion sits relative to the player.
+const COMPANION_OFFSET: Vec2 = Vec2::new(-70.0, 20.0);
+/// How quickly the companion catches up with its spot, per second.
+const COMPANION_FOLLOW: f32 = 6.0;
+const COMPANION_FIRE_SECONDS: f32 = 1.2;
+const COMPANION_SCALE: f32 = 0.25;
+const COMPANION_LASER_SPEED: f32 = 1.2;
+
+pub struct CompanionPlugin;
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), companion_despawn_all)
+            .add_systems(OnExit(GameState::Playing), companion_despawn_all)
+            .add_systems(
+                Update,
+                (companion_earn, companion_follow, companion_fire)
+                    .chain()
+                    .run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+fn companion_despawn_all(mut commands: Commands, query: Query<Entity, With<Companion>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn companion_earn(
+    mut commands: Commands,
+    combo: Res<ComboState>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    player_query: Query<&Transform, With<Player>>,
+    companion_query: Query<(), With<Companion>>,
+) {
+    if config.companion_combo == 0
+        || combo.count < config.companion_combo
+        || !companion_query.is_empty()
+    {
+        return;
+    }
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+
+    commands.spawn((
+        Sprite::from_image(game_textures.player.clone()),
+        Transform {
+            translation: player_tf.translation + COMPANION_OFFSET.extend(0.0),
+            scale: Vec3::new(COMPANION_SCALE, COMPANION_SCALE, 1.0),
+            ..default()
+        },
+        Companion {
+            fire_timer: Timer::from_seconds(COMPANION_FIRE_SECONDS, TimerMode::Repeating),
+        },
+    ));
+}
+
+/// Trails the player, and goes down with them.
+fn companion_follow(
+    mut commands: Commands,
+    time: Res<Time>,
+    player_query: Query<&Transform, (With<Player>, Without<Companion>)>,
+    mut companion_query: Query<(Entity, &mut Transform), With<Companion>>,
+) {
+    let Ok(player_tf) = player_query.single() else {
+        for (entity, _) in &companion_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let target = player_tf.translation.truncate() + COMPANION_OFFSET;
+    let t = (COMPANION_FOLLOW * time.delta_secs()).min(1.0);
+    for (_, mut transform) in &mut companion_query {
+        let position = transform.translation.truncate().lerp(target, t);
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
+
+fn companion_fire(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    game_textures: Res<GameTextures>,
+    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    mut companion_query: Query<(&mut Companion, &Transform)>,
+) {
+    for (mut companion, transform) in &mut companion_query {
+        if !companion.fire_timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let from = transform.translation.truncate();
+        let target = nearest_enemy(
+            from,
+            enemy_query
+                .iter()
+                .map(|(entity, tf)| (entity, tf.translation.truncate())),
+        )
+        .and_then(|entity| enemy_query.get(entity).ok());
+        let Some((_, target_tf)) = target else {
+            continue;
+        };
+
+        let direction = (target_tf.translation.truncate() - from).normalize_or(Vec2::Y);
+        spawn_player_laser(
+            &mut commands,
+            &config,
+            *palette,
+            game_textures.player_laser.clone(),
+            from,
+            direction * COMPANION_LASER_SPEED,
+        );
+    }
+}
@@ -1,9 +1,18 @@
+use std::collections::HashSet;
+
 use bevy::{
+    color::Color,
+    ecs::{
+        entity::Entity,
+        query::{Or, With, Without},
+    },
     math::Vec2,
     prelude::Component,
     time::{Timer, TimerMode},
 };
 
+use crate::{PLAYER_BLINK_INTERVAL, PLAYER_INVULNERABILITY, SCORE_POPUP_SECONDS, menu::MenuAction};
+
 #[derive(Component)]
 pub struct MainMenu;
 
@@ -16,6 +25,18 @@ pub struct Velocity {
     pub y: f32,
 }
 
+/// A player laser that carries on through the enemies it hits, remembering them so
+/// it damages each only once.
+#[derive(Component, Default)]
+pub struct Piercing {
+    pub hit: HashSet<Entity>,
+}
+
+/// Share of `SpriteSize` that counts for collisions, so hits have to land on the
+/// visible hull rather than the sprite's empty corners.
+#[derive(Component)]
+pub struct Hitbox(pub f32);
+
 #[derive(Component)]
 pub struct SpriteSize(pub Vec2);
 impl From<(f32, f32)> for SpriteSize {
@@ -24,9 +45,63 @@ impl From<(f32, f32)> for SpriteSize {
     }
 }
 
+impl SpriteSize {
+    /// Half extents of the sprite's box once drawn at `scale`, as `Aabb2d` wants them.
+    pub fn half_extents(&self, scale: Vec2) -> Vec2 {
+        self.0 * scale / 2.0
+    }
+}
+
 #[derive(Component)]
 pub struct Player;
 
+/// Translucent ship retracing the best run; purely visual.
+#[derive(Component)]
+pub struct Ghost;
+
+#[derive(Component)]
+pub struct StatusUI;
+
+/// Projectile that steers toward the nearest enemy, turning at most `turn_rate`
+/// radians per second.
+#[derive(Component)]
+pub struct Homing {
+    pub turn_rate: f32,
+}
+
+/// Turret that trails the player and fires at the nearest enemy.
+#[derive(Component)]
+pub struct Companion {
+    pub fire_timer: Timer,
+}
+
+#[derive(Component)]
+pub struct ComboUI;
+
+/// Center-screen kill-streak callout; a new one replaces it.
+#[derive(Component)]
+pub struct StreakAnnouncement;
+
+#[derive(Component)]
+pub struct HeatUI;
+
+/// Protects the player from damage after a respawn; the sprite blinks on a fixed
+/// time interval so it looks the same at any frame rate.
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+    pub blink: Timer,
+}
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(PLAYER_INVULNERABILITY, TimerMode::Once),
+            blink: Timer::from_seconds(PLAYER_BLINK_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct FromPlayer;
 
@@ -36,6 +111,118 @@ pub struct Enemy;
 #[derive(Component)]
 pub struct FromEnemy;
 
+/// Periodically hides an enemy; while cloaked it can neither be hit nor fire.
+#[derive(Component)]
+pub struct Cloak {
+    pub timer: Timer,
+    pub cloaked: bool,
+}
+
+/// Present on a cloaking enemy while it is visible and vulnerable.
+#[derive(Component)]
+pub struct Uncloaked;
+
+/// Present on an enemy while it materializes; it can't be hit or fire until it's gone.
+#[derive(Component)]
+pub struct Spawning;
+
+/// Drives an enemy's fade and scale-in after it spawns.
+#[derive(Component)]
+pub struct SpawnAnim {
+    pub timer: Timer,
+}
+
+/// Flies a new enemy in from off-screen along a curve to its slot. It stays
+/// `Spawning` until it arrives.
+#[derive(Component)]
+pub struct Entering {
+    pub from: Vec2,
+    pub target: Vec2,
+    pub timer: Timer,
+}
+
+/// Enemies that can currently be hit and fire: everything but cloaked enemies and
+/// enemies still materializing.
+pub type Exposed = (Or<(Without<Cloak>, With<Uncloaked>)>, Without<Spawning>);
+
+/// How an enemy shoots when its volley comes up.
+#[derive(Component, Clone, Copy)]
+pub enum FirePattern {
+    /// Two lasers straight down from its wings.
+    Twin,
+    /// Lasers fanning outward from its center.
+    Spread,
+    /// A single laser at where the player is now.
+    Aimed,
+}
+
+/// An enemy charging up its next volley, with the indicator entity drawn under it.
+#[derive(Component)]
+pub struct FiringTimer {
+    pub timer: Timer,
+    pub indicator: Entity,
+}
+
+/// Armor covering the side of an enemy that `facing` points at; hits there are deflected.
+#[derive(Component)]
+pub struct FrontArmor {
+    pub facing: Vec2,
+}
+
+/// Buff for the last enemy alive: faster, firing on its own quicker timer, tinted red.
+#[derive(Component)]
+pub struct Berserk {
+    pub fire_timer: Timer,
+    /// Sprite color to restore once the buff is lifted.
+    pub previous_color: Color,
+}
+
+/// Takes several player hits to bring down, handled in `boss_hit` rather than the
+/// generic laser collision.
+#[derive(Component)]
+pub struct Boss {
+    pub health: u32,
+}
+
+/// Full-screen banner flashed before a boss arrives.
+#[derive(Component)]
+pub struct BossWarning;
+
+/// Falling pickup that refills a bomb charge.
+#[derive(Component)]
+pub struct BombPickup;
+
+/// Falling pickup that restores a life, or shields a ship that has all of them.
+#[derive(Component)]
+pub struct ShieldPickup;
+
+/// Enemy that makes every other enemy near it immune to player lasers.
+#[derive(Component)]
+pub struct Support;
+
+/// Within reach of a `Support` enemy; player lasers glance off. Refreshed every frame.
+#[derive(Component)]
+pub struct Protected;
+
+/// Enemy that dives for the bottom of the screen and blows up there if not shot down.
+#[derive(Component)]
+pub struct Bomber;
+
+/// Enemy whose sideways movement follows the player's, copying it or, with `mirror`,
+/// going the opposite way.
+#[derive(Component)]
+pub struct Mimic {
+    pub mirror: bool,
+}
+
+/// Enemy that always leaves a `ShieldPickup` behind when destroyed.
+#[derive(Component)]
+pub struct DropsShield;
+
+/// Soaks up the next hit the player takes instead of a life.
+#[derive(Component)]
+pub struct Shield;
+
 #[derive(Component)]
 pub struct Laser;
 
@@ -44,14 +231,149 @@ pub struct Movable {
     pub auto_despawn: bool,
 }
 
+/// Deployed area that slows enemies and enemy lasers inside its radius.
+#[derive(Component)]
+pub struct SlowField {
+    pub radius: f32,
+    pub timer: Timer,
+}
+
+/// Marks an entity currently inside a slow field.
+#[derive(Component)]
+pub struct Slowed;
+
+/// Despawns the entity once the timer elapses, whether or not it left the screen.
+#[derive(Component)]
+pub struct Lifetime(pub Timer);
+
+impl Lifetime {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self(Timer::from_seconds(seconds, TimerMode::Once))
+    }
+}
+
 #[derive(Component)]
 pub struct Explosion;
 
 #[derive(Component)]
 pub struct ExplosionTimer(pub Timer);
 
-impl Default for ExplosionTimer {
+impl ExplosionTimer {
+    /// Advances the animation one frame every `frame_duration` seconds.
+    pub fn new(frame_duration: f32) -> Self {
+        Self(Timer::from_seconds(frame_duration, TimerMode::Repeating))
+    }
+}
+
+/// Points earned, drifting up from where they were scored until `timer` runs out.
+#[derive(Component)]
+pub struct ScorePopup {
+    pub timer: Timer,
+}
+
+impl Default for ScorePopup {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(SCORE_POPUP_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+/// Neutral rock that blocks lasers from both sides. Large ones split in two once the
+/// player's lasers have worn down `hits_left`.
+#[derive(Component)]
+pub struct Asteroid {
+    pub large: bool,
+    pub hits_left: u32,
+}
+
+/// Debris thrown out by an explosion, fading away over its timer.
+#[derive(Component)]
+pub struct Particle(pub Timer);
+
+#[derive(Component)]
+pub struct AchievementsMenu;
+
+#[derive(Component)]
+pub struct Toast(pub Timer);
+
+impl Default for Toast {
     fn default() -> Self {
-        Self(Timer::from_seconds(0.05, TimerMode::Repeating))
+        Self(Timer::from_seconds(3.0, TimerMode::Once))
+    }
+}
+
+#[derive(Component)]
+pub struct MenuOptions;
+
+#[derive(Component)]
+pub struct MenuButton(pub MenuAction);
+
+#[derive(Component)]
+pub struct LeaderboardPanel;
+
+#[derive(Component)]
+pub struct SettingsMenu;
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Component)]
+pub struct MutatorMenu;
+
+/// Darkness drawn over the playfield by the fog mutator.
+#[derive(Component)]
+pub struct FogOverlay;
+
+#[derive(Component)]
+pub struct ResultsScreen;
+
+#[derive(Component)]
+pub struct DefenseLineSprite;
+
+#[derive(Component)]
+pub struct MainCamera;
+
+#[derive(Clone, Copy)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Component)]
+pub struct EdgeGlow(pub Edge);
+
+#[derive(Component)]
+pub enum TouchButton {
+    Left,
+    Right,
+    Fire,
+}
+
+#[derive(Component)]
+pub struct DebugOverlayUI;
+
+#[derive(Component)]
+pub struct WatermarkUI;
+
+#[derive(Component)]
+pub struct TutorialUI;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprite_size_from_tuple() {
+        let size = SpriteSize::from((144.0, 75.0));
+        assert_eq!(size.0, Vec2::new(144.0, 75.0));
+    }
+
+    #[test]
+    fn half_extents_at_half_scale() {
+        let size = SpriteSize::from((144.0, 75.0));
+        assert_eq!(size.half_extents(Vec2::splat(0.5)), Vec2::new(36.0, 18.75));
     }
 }
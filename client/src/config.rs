@@ -0,0 +1,180 @@
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, VideoModeSelection, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+/// Gameplay tunables. Defaults match the original hard-coded values and can be
+/// overridden per field through `game_config.json` in the config directory.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Pixels per second travelled at a velocity of 1.0.
+    pub base_speed: f32,
+    pub sprite_scale: f32,
+    pub player_size: Vec2,
+    pub player_laser_size: Vec2,
+    pub enemy_size: Vec2,
+    pub enemy_laser_size: Vec2,
+    pub player_lives: u32,
+    /// Velocity gained per second while steering, with momentum on.
+    pub player_acceleration: f32,
+    /// Velocity lost per second with nothing held, with momentum on.
+    pub player_friction: f32,
+    /// Share of the ship's speed kept, reversed, when it hits the screen edge with
+    /// momentum on.
+    pub wall_bounce: f32,
+    /// Fixed seed for reproducible runs; random when unset. `RUST_INVADERS_SEED` in the
+    /// environment takes precedence.
+    pub rng_seed: Option<u64>,
+    pub player_max_lasers: usize,
+    pub player_fire_cooldown: f32,
+    /// Gun heat added by each shot, out of 1.0 at which it overheats.
+    pub heat_per_shot: f32,
+    /// Gun heat shed per second.
+    pub heat_cooling_rate: f32,
+    pub laser_lifetime: f32,
+    /// Distance past the screen edge at which lasers, enemies and other movables are
+    /// despawned.
+    pub despawn_margin: f32,
+    /// Distance past the left, right and top edges at which drifting enemies turn back.
+    pub enemy_bound_margin: f32,
+    /// Enemy cap at the start of a run on normal difficulty, and the least the
+    /// difficulty director lowers it to.
+    pub max_enemies: u32,
+    /// Most the difficulty director raises the enemy cap to.
+    pub max_enemies_upgrade: u32,
+    /// Score at which the background takes its first tint.
+    pub max_enemies_upgrade_score: u32,
+    pub laser_upgrade_score: u32,
+    pub spread_shot_score: u32,
+    /// Horizontal velocity of the outer spread shot lasers relative to their speed.
+    pub spread_shot_angle: f32,
+    pub piercing_score: u32,
+    /// Score from which player lasers shoot down enemy lasers.
+    pub laser_intercept_score: u32,
+    pub homing_missile_score: u32,
+    pub missile_cooldown: f32,
+    pub enemy_fire_interval: f32,
+    pub enemy_min_fire_interval: f32,
+    /// Lasers in a spread volley, kept between 3 and 5.
+    pub enemy_spread_lasers: u32,
+    /// Radians between neighbouring lasers of a spread volley.
+    pub enemy_spread_angle: f32,
+    pub cloak_chance: f64,
+    pub armored_chance: f64,
+    pub medic_chance: f64,
+    pub support_chance: f64,
+    pub bomber_chance: f64,
+    pub mimic_chance: f64,
+    /// Combo that earns a companion turret; 0 turns companions off.
+    pub companion_combo: u32,
+    /// Points per continue credit; 0 turns continues off.
+    pub credit_score: u32,
+    pub window_mode: WindowModeSetting,
+    /// Window size in logical pixels when windowed. Any aspect ratio works, e.g. 1280x720.
+    pub window_resolution: Vec2,
+}
+
+/// How the window is shown at startup; F11 switches between windowed and fullscreen.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            WindowModeSetting::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            base_speed: 600.0,
+            sprite_scale: 0.5,
+            player_size: Vec2::new(144., 75.),
+            player_laser_size: Vec2::new(9., 54.),
+            enemy_size: Vec2::new(144., 75.),
+            enemy_laser_size: Vec2::new(17., 55.),
+            player_lives: 3,
+            player_acceleration: 6.0,
+            player_friction: 4.0,
+            wall_bounce: 0.4,
+            rng_seed: None,
+            player_max_lasers: 10,
+            player_fire_cooldown: 0.2,
+            heat_per_shot: 0.1,
+            heat_cooling_rate: 0.3,
+            laser_lifetime: 5.0,
+            despawn_margin: 200.0,
+            enemy_bound_margin: 50.0,
+            max_enemies: 3,
+            max_enemies_upgrade: 10,
+            max_enemies_upgrade_score: 5,
+            laser_upgrade_score: 50,
+            spread_shot_score: 25,
+            spread_shot_angle: 0.25,
+            piercing_score: 150,
+            laser_intercept_score: 100,
+            homing_missile_score: 75,
+            missile_cooldown: 1.0,
+            enemy_fire_interval: 1.0,
+            enemy_min_fire_interval: 0.3,
+            enemy_spread_lasers: 3,
+            enemy_spread_angle: 0.3,
+            cloak_chance: 0.15,
+            armored_chance: 0.1,
+            medic_chance: 0.05,
+            support_chance: 0.05,
+            bomber_chance: 0.05,
+            mimic_chance: 0.05,
+            companion_combo: 15,
+            credit_score: 100,
+            window_mode: WindowModeSetting::Windowed,
+            window_resolution: Vec2::new(800., 800.),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_original_constants() {
+        let config = GameConfig::default();
+        assert_eq!(config.base_speed, 600.0);
+        assert_eq!(config.sprite_scale, 0.5);
+        assert_eq!(config.player_size, Vec2::new(144., 75.));
+        assert_eq!(config.player_laser_size, Vec2::new(9., 54.));
+        assert_eq!(config.enemy_size, Vec2::new(144., 75.));
+        assert_eq!(config.enemy_laser_size, Vec2::new(17., 55.));
+        assert_eq!(config.player_lives, 3);
+        assert_eq!(config.player_max_lasers, 10);
+        assert_eq!(config.player_fire_cooldown, 0.2);
+        assert_eq!(config.laser_lifetime, 5.0);
+        assert_eq!(config.max_enemies, 3);
+        assert_eq!(config.max_enemies_upgrade, 10);
+        assert_eq!(config.max_enemies_upgrade_score, 5);
+        assert_eq!(config.laser_upgrade_score, 50);
+        assert_eq!(config.spread_shot_score, 25);
+        assert_eq!(config.spread_shot_angle, 0.25);
+        assert_eq!(config.enemy_fire_interval, 1.0);
+        assert_eq!(config.enemy_min_fire_interval, 0.3);
+        assert_eq!(config.cloak_chance, 0.15);
+        assert_eq!(config.armored_chance, 0.1);
+    }
+}
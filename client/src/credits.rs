@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{
+    ContinuedRun, GameState, Lives, Score,
+    components::{Enemy, Laser, ResultsScreen},
+    config::GameConfig,
+    difficulty::Difficulty,
+    mutators::Mutators,
+};
+
+/// Continues banked this run, one for every `credit_score` points.
+#[derive(Resource, Default)]
+pub struct Credits {
+    pub available: u32,
+    /// Credits handed out so far, so each score step only pays once.
+    pub earned: u32,
+}
+
+pub struct CreditsPlugin;
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Credits>()
+            .add_systems(Update, credits_earn.run_if(in_state(GameState::Playing)))
+            .add_systems(Update, continue_run.run_if(in_state(GameState::GameOver)));
+    }
+}
+
+fn credits_earn(score: Res<Score>, config: Res<GameConfig>, mut credits: ResMut<Credits>) {
+    if config.credit_score == 0 {
+        return;
+    }
+    let due = **score / config.credit_score;
+    if due > credits.earned {
+        credits.available += due - credits.earned;
+        credits.earned = due;
+    }
+}
+
+/// Spends a credit to carry on from the results screen with the score kept, the
+/// playfield cleared and lives refilled.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn continue_run(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut credits: ResMut<Credits>,
+    mut lives: ResMut<Lives>,
+    mut continued: ResMut<ContinuedRun>,
+    config: Res<GameConfig>,
+    mutators: Res<Mutators>,
+    difficulty: Res<Difficulty>,
+    leftover_query: Query<Entity, Or<(With<Enemy>, With<Laser>, With<ResultsScreen>)>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !input.just_pressed(KeyCode::KeyC) || credits.available == 0 {
+        return;
+    }
+    credits.available -= 1;
+    for entity in &leftover_query {
+        commands.entity(entity).despawn();
+    }
+    **lives = difficulty.starting_lives(mutators.starting_lives(config.player_lives));
+    **continued = true;
+    next_state.set(GameState::Playing);
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    ReduceEffects,
+    components::{Edge, EdgeGlow, FromEnemy, Laser, Player, Velocity},
+};
+
+const DANGER_RADIUS: f32 = 250.0;
+const DANGER_MAX_ALPHA: f32 = 0.35;
+const EDGE_THICKNESS: f32 = 12.0;
+
+pub struct DangerPlugin;
+impl Plugin for DangerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, danger_edges_spawn)
+            .add_systems(Update, danger_edges_update);
+    }
+}
+
+fn danger_edges_spawn(mut commands: Commands) {
+    for edge in [Edge::Left, Edge::Right, Edge::Top, Edge::Bottom] {
+        let (width, height) = match edge {
+            Edge::Left | Edge::Right => (Val::Px(EDGE_THICKNESS), Val::Percent(100.0)),
+            Edge::Top | Edge::Bottom => (Val::Percent(100.0), Val::Px(EDGE_THICKNESS)),
+        };
+        let mut node = Node {
+            position_type: PositionType::Absolute,
+            width,
+            height,
+            ..default()
+        };
+        match edge {
+            Edge::Left => node.left = Val::Px(0.0),
+            Edge::Right => node.right = Val::Px(0.0),
+            Edge::Top => node.top = Val::Px(0.0),
+            Edge::Bottom => node.bottom = Val::Px(0.0),
+        }
+        match edge {
+            Edge::Left | Edge::Right => node.top = Val::Px(0.0),
+            Edge::Top | Edge::Bottom => node.left = Val::Px(0.0),
+        }
+
+        commands.spawn((
+            node,
+            BackgroundColor(Color::srgba(1.0, 0.0, 0.0, 0.0)),
+            EdgeGlow(edge),
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn danger_edges_update(
+    reduce_effects: Res<ReduceEffects>,
+    player_query: Query<&Transform, With<Player>>,
+    laser_query: Query<(&Transform, &Velocity), (With<Laser>, With<FromEnemy>)>,
+    mut edge_query: Query<(&EdgeGlow, &mut BackgroundColor)>,
+) {
+    // threat accumulated towards each edge: left, right, top, bottom
+    let mut threat = [0.0_f32; 4];
+
+    if let Ok(player_tf) = player_query.single()
+        && !**reduce_effects
+    {
+        let player_pos = player_tf.translation.truncate();
+        for (laser_tf, velocity) in &laser_query {
+            let offset = laser_tf.translation.truncate() - player_pos;
+            let distance = offset.length();
+            if distance > DANGER_RADIUS {
+                continue;
+            }
+
+            let speed = Vec2::new(velocity.x, velocity.y).length();
+            let level = (1.0 - distance / DANGER_RADIUS) * speed;
+            let direction = offset.normalize_or_zero();
+            threat[0] += level * (-direction.x).max(0.0);
+            threat[1] += level * direction.x.max(0.0);
+            threat[2] += level * direction.y.max(0.0);
+            threat[3] += level * (-direction.y).max(0.0);
+        }
+    }
+
+    for (edge, mut background) in &mut edge_query {
+        let level = threat[edge.0 as usize].min(1.0);
+        background.0.set_alpha(level * DANGER_MAX_ALPHA);
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::{
+    DespawnMargin, EnemyCount, GameState, MaxEnemies, Sandbox, Score,
+    components::{DebugOverlayUI, Enemy, Hitbox, Laser, Player, SpriteSize, WatermarkUI},
+    config::GameConfig,
+    hitbox_half_size,
+};
+
+const PLAYER_HITBOX_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
+const ENEMY_HITBOX_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+const LASER_HITBOX_COLOR: Color = Color::srgb(1.0, 1.0, 0.2);
+
+#[derive(Resource, Deref, DerefMut, Default)]
+struct DebugOverlay(bool);
+
+/// Outlines the boxes the collision systems test, to see why a hit did or didn't count.
+#[derive(Resource, Deref, DerefMut, Default, PartialEq)]
+struct HitboxDraw(bool);
+
+/// Shows the frame count in a corner so bug reports and screenshots can be matched to
+/// an exact frame. Debug builds only.
+#[cfg(debug_assertions)]
+#[derive(Resource, Deref, DerefMut, Default)]
+struct Watermark(bool);
+
+pub struct DebugPlugin;
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .init_resource::<DebugOverlay>()
+            .add_systems(Startup, debug_overlay_spawn)
+            .add_systems(
+                Update,
+                (debug_overlay_toggle, debug_overlay_update)
+                    .chain()
+                    .run_if(not(in_state(GameState::MainMenu))),
+            )
+            .add_systems(OnEnter(GameState::MainMenu), debug_overlay_hide)
+            .init_resource::<HitboxDraw>()
+            .add_systems(
+                Update,
+                (
+                    hitbox_draw_toggle,
+                    hitbox_draw.run_if(resource_equals(HitboxDraw(true))),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, despawn_margin_toggle);
+
+        #[cfg(debug_assertions)]
+        app.init_resource::<Watermark>()
+            .add_systems(Startup, watermark_spawn)
+            .add_systems(Update, (watermark_toggle, watermark_update).chain());
+    }
+}
+
+fn debug_overlay_spawn(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont::from_font_size(14.0),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        DebugOverlayUI,
+    ));
+}
+
+fn debug_overlay_toggle(
+    input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut query: Query<&mut Visibility, With<DebugOverlayUI>>,
+) {
+    if !input.just_pressed(KeyCode::F3) {
+        return;
+    }
+    **overlay = !**overlay;
+    for mut visibility in &mut query {
+        *visibility = if **overlay {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Turns the overlay off on the way back to the menu, so the next F3 shows it again.
+fn debug_overlay_hide(
+    mut overlay: ResMut<DebugOverlay>,
+    mut query: Query<&mut Visibility, With<DebugOverlayUI>>,
+) {
+    **overlay = false;
+    for mut visibility in &mut query {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn debug_overlay_update(
+    overlay: Res<DebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    enemy_count: Res<EnemyCount>,
+    max_enemies: Res<MaxEnemies>,
+    score: Res<Score>,
+    sandbox: Res<Sandbox>,
+    despawn_margin: Res<DespawnMargin>,
+    laser_query: Query<(), With<Laser>>,
+    mut query: Query<&mut Text, With<DebugOverlayUI>>,
+) {
+    if !**overlay {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+
+    for mut text in &mut query {
+        text.0 = format!(
+            "fps: {:.0}\nenemies: {}/{}\nscore: {}\nlasers: {}\nsandbox: {}\ndespawn margin [F6]: {}",
+            fps,
+            **enemy_count,
+            **max_enemies,
+            **score,
+            laser_query.iter().len(),
+            **sandbox,
+            despawn_margin.map_or("off".to_string(), |margin| margin.to_string())
+        );
+    }
+}
+
+fn hitbox_draw_toggle(input: Res<ButtonInput<KeyCode>>, mut hitbox_draw: ResMut<HitboxDraw>) {
+    if input.just_pressed(KeyCode::F5) {
+        **hitbox_draw = !**hitbox_draw;
+    }
+}
+
+/// Lets lasers and enemies live on off-screen, to watch what they do out there and to
+/// see how the game copes with far more of them than usual.
+fn despawn_margin_toggle(
+    input: Res<ButtonInput<KeyCode>>,
+    config: Res<GameConfig>,
+    mut despawn_margin: ResMut<DespawnMargin>,
+) {
+    if input.just_pressed(KeyCode::F6) {
+        **despawn_margin = match **despawn_margin {
+            Some(_) => None,
+            None => Some(config.despawn_margin),
+        };
+    }
+}
+
+/// Draws the same boxes the collision systems build, from the same half-extents.
+#[allow(clippy::type_complexity)]
+fn hitbox_draw(
+    mut gizmos: Gizmos,
+    query: Query<
+        (
+            &Transform,
+            &SpriteSize,
+            Option<&Hitbox>,
+            Has<Player>,
+            Has<Enemy>,
+        ),
+        Or<(With<Player>, With<Enemy>, With<Laser>)>,
+    >,
+) {
+    for (transform, size, hitbox, player, enemy) in &query {
+        let color = if player {
+            PLAYER_HITBOX_COLOR
+        } else if enemy {
+            ENEMY_HITBOX_COLOR
+        } else {
+            LASER_HITBOX_COLOR
+        };
+        let half_size = hitbox_half_size(size, transform, hitbox);
+        gizmos.rect_2d(
+            Isometry2d::from_translation(transform.translation.truncate()),
+            half_size * 2.0,
+            color,
+        );
+    }
+}
+
+#[cfg(debug_assertions)]
+fn watermark_spawn(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextFont::from_font_size(12.0),
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.5)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(2.0),
+            left: Val::Px(5.0),
+            ..default()
+        },
+        Visibility::Hidden,
+        WatermarkUI,
+    ));
+}
+
+#[cfg(debug_assertions)]
+fn watermark_toggle(
+    input: Res<ButtonInput<KeyCode>>,
+    mut watermark: ResMut<Watermark>,
+    mut query: Query<&mut Visibility, With<WatermarkUI>>,
+) {
+    if !input.just_pressed(KeyCode::F4) {
+        return;
+    }
+    **watermark = !**watermark;
+    for mut visibility in &mut query {
+        *visibility = if **watermark {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[cfg(debug_assertions)]
+fn watermark_update(
+    watermark: Res<Watermark>,
+    frame_count: Res<bevy::diagnostic::FrameCount>,
+    rng: Res<crate::GameRng>,
+    mut query: Query<&mut Text, With<WatermarkUI>>,
+) {
+    if !**watermark {
+        return;
+    }
+    for mut text in &mut query {
+        text.0 = format!("frame {}  seed {}", frame_count.0, rng.seed);
+    }
+}
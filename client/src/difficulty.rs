@@ -0,0 +1,150 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState, get_config_path,
+    save::{load_json, save_json},
+};
+
+/// Selected on the main menu and remembered across launches.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Only reachable from the menu button: a single life and relentless enemies.
+    Hardcore,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Hardcore => "Hardcore",
+        }
+    }
+
+    /// Lives at the start of a run, given the normal count. Hardcore dies in one hit.
+    pub fn starting_lives(&self, normal: u32) -> u32 {
+        match self {
+            Difficulty::Hardcore => 1,
+            _ => normal,
+        }
+    }
+
+    /// `MaxEnemies` at the start of a run, given the normal-difficulty cap.
+    pub fn starting_max_enemies(&self, normal: u32) -> u32 {
+        match self {
+            Difficulty::Easy => normal.saturating_sub(1).max(1),
+            Difficulty::Normal => normal,
+            Difficulty::Hard => normal + 2,
+            Difficulty::Hardcore => normal + 4,
+        }
+    }
+
+    /// Multiplier applied to the seconds between enemy volleys.
+    pub fn fire_interval_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.7,
+            Difficulty::Hardcore => 0.5,
+        }
+    }
+
+    /// Multiplier applied to enemy movement.
+    pub fn enemy_speed_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+            Difficulty::Hardcore => 1.5,
+        }
+    }
+
+    /// Multiplier on the player's hitbox; easy mode forgives near misses.
+    pub fn player_hitbox_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            _ => 1.0,
+        }
+    }
+
+    /// Multiplier on enemy hitboxes; easy mode makes them easier to hit.
+    pub fn enemy_hitbox_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.2,
+            _ => 1.0,
+        }
+    }
+
+    /// Next setting up, wrapping back around to the easiest.
+    pub fn cycled(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Hardcore,
+            Difficulty::Hardcore => Difficulty::Easy,
+        }
+    }
+
+    /// The arrow keys stop at hard; hardcore has to be picked on purpose.
+    fn harder(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Hardcore => Difficulty::Hardcore,
+            _ => Difficulty::Hard,
+        }
+    }
+
+    fn easier(self) -> Self {
+        match self {
+            Difficulty::Hardcore => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Normal,
+            _ => Difficulty::Easy,
+        }
+    }
+}
+
+#[derive(Resource, Deref)]
+struct DifficultyPath(PathBuf);
+
+pub struct DifficultyPlugin;
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        let difficulty_path = get_config_path("difficulty.json").unwrap_or_default();
+        let difficulty: Difficulty = load_json(&difficulty_path);
+
+        app.insert_resource(difficulty)
+            .insert_resource(DifficultyPath(difficulty_path))
+            .add_systems(
+                Update,
+                (select_difficulty, save_difficulty)
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+fn select_difficulty(input: Res<ButtonInput<KeyCode>>, mut difficulty: ResMut<Difficulty>) {
+    let selected = if input.just_pressed(KeyCode::ArrowLeft) {
+        difficulty.easier()
+    } else if input.just_pressed(KeyCode::ArrowRight) {
+        difficulty.harder()
+    } else {
+        return;
+    };
+    difficulty.set_if_neq(selected);
+}
+
+/// Writes the difficulty out whenever it changes, from the arrow keys or the menu.
+fn save_difficulty(difficulty: Res<Difficulty>, difficulty_path: Res<DifficultyPath>) {
+    if difficulty.is_changed() && !difficulty.is_added() {
+        let _ = save_json(&difficulty_path, &*difficulty);
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{
+    ContinuedRun, GameState, MaxEnemies,
+    config::GameConfig,
+    difficulty::Difficulty,
+    events::{EnemyKilled, PlayerFired, PlayerHit, WaveStarted},
+    mutators::Mutators,
+    pause::Pause,
+};
+
+/// Pressure gained per second survived without being hit.
+const SURVIVAL_PRESSURE: f32 = 0.004;
+/// Pressure lost on every hit the player takes.
+const HIT_RELIEF: f32 = 0.25;
+/// Share of shots that should land for the pressure to hold steady.
+const TARGET_ACCURACY: f32 = 0.3;
+/// Pressure gained per wave for every point of accuracy above the target.
+const ACCURACY_PRESSURE: f32 = 0.5;
+/// Share of the shot and kill tallies carried into the next wave, so older waves fade.
+const TALLY_CARRY: f32 = 0.5;
+/// Seconds between enemy volleys are scaled from the first to the second as pressure
+/// goes from 0.0 to 1.0.
+const FIRE_INTERVAL_SCALE_RANGE: (f32, f32) = (1.0, 0.7);
+
+/// Adaptive difficulty: a rolling pressure score of how well the player is coping,
+/// applied to the enemy cap and fire rate at the start of each wave.
+#[derive(Resource, Default)]
+pub struct Director {
+    /// From 0.0 while the player struggles to 1.0 while they cruise.
+    pub pressure: f32,
+}
+
+impl Director {
+    /// Multiplier applied to the seconds between enemy volleys.
+    pub fn fire_interval_scale(&self) -> f32 {
+        let (easy, hard) = FIRE_INTERVAL_SCALE_RANGE;
+        easy + (hard - easy) * self.pressure
+    }
+}
+
+/// What the player has done since the director last adjusted.
+#[derive(Resource, Default)]
+struct DirectorTally {
+    pressure: f32,
+    shots: f32,
+    kills: f32,
+}
+
+pub struct DirectorPlugin;
+impl Plugin for DirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Director>()
+            .init_resource::<DirectorTally>()
+            .add_systems(OnEnter(GameState::Playing), director_reset)
+            .add_systems(
+                Update,
+                (director_track, director_adjust)
+                    .chain()
+                    .run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+fn director_reset(
+    continued: Res<ContinuedRun>,
+    mut director: ResMut<Director>,
+    mut tally: ResMut<DirectorTally>,
+) {
+    // a continue keeps the pressure it died with; the hit already eased it off
+    if !**continued {
+        *director = Director::default();
+        *tally = DirectorTally::default();
+    }
+}
+
+fn director_track(
+    time: Res<Time>,
+    mut tally: ResMut<DirectorTally>,
+    mut fired: EventReader<PlayerFired>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut player_hit: EventReader<PlayerHit>,
+) {
+    tally.shots += fired.read().count() as f32;
+    tally.kills += enemy_killed.read().count() as f32;
+    let hits = player_hit.read().count() as f32;
+    tally.pressure += SURVIVAL_PRESSURE * time.delta_secs() - HIT_RELIEF * hits;
+}
+
+/// Folds the tally into the pressure as each wave starts and sets the enemy cap from it,
+/// between the run's starting cap and the upgraded one.
+fn director_adjust(
+    mut director: ResMut<Director>,
+    mut tally: ResMut<DirectorTally>,
+    mut max_enemies: ResMut<MaxEnemies>,
+    config: Res<GameConfig>,
+    difficulty: Res<Difficulty>,
+    mutators: Res<Mutators>,
+    mut wave_started: EventReader<WaveStarted>,
+) {
+    if wave_started.read().count() == 0 {
+        return;
+    }
+
+    let accuracy = if tally.shots > 0.0 {
+        (tally.kills / tally.shots).min(1.0)
+    } else {
+        TARGET_ACCURACY
+    };
+    let pressure =
+        director.pressure + tally.pressure + (accuracy - TARGET_ACCURACY) * ACCURACY_PRESSURE;
+    director.pressure = pressure.clamp(0.0, 1.0);
+    tally.pressure = 0.0;
+    tally.shots *= TALLY_CARRY;
+    tally.kills *= TALLY_CARRY;
+
+    let min = mutators.max_enemies(difficulty.starting_max_enemies(config.max_enemies));
+    let max = mutators.max_enemies(config.max_enemies_upgrade).max(min);
+    **max_enemies = min + ((max - min) as f32 * director.pressure).round() as u32;
+}
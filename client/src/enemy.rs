@@ -2,91 +2,740 @@ use std::time::Duration;
 
 use bevy::{prelude::*, time::common_conditions::on_timer};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    ENEMY_LASER_SIZE, ENEMY_SIZE, EnemyCount, GameTextures, MaxEnemies, SPRITE_SCALE, WinSize,
-    components::{Enemy, FromEnemy, Laser, Movable, SpriteSize, Velocity},
+    BERSERK_FIRE_RATE, CLOAK_HIDDEN_SECONDS, CLOAK_VISIBLE_SECONDS, DEFENSE_LINE_OFFSET,
+    DefenseLine, ENEMY_DESCENT_SPEED, ENEMY_HITBOX_SCALE, ENEMY_HOMING_STRENGTH, EnemyCount,
+    EnemyMovement, GameRng, GameState, GameTextures, MaxEnemies, PLAYER_DEATH_SHAKE, Score,
+    WinSize,
+    boss::BossState,
+    camera::ScreenShake,
+    components::{
+        Berserk, Bomber, Boss, Cloak, DropsShield, Enemy, Entering, Exposed, FirePattern,
+        FiringTimer, FromEnemy, FrontArmor, Hitbox, Laser, Lifetime, Mimic, Movable, Player,
+        Protected, SpawnAnim, Spawning, SpriteSize, Support, Uncloaked, Velocity,
+    },
+    config::GameConfig,
+    difficulty::Difficulty,
+    director::Director,
+    events::{BomberDetonated, SpawnExplosion},
+    mutators::Mutators,
+    settings::Palette,
+    wave::{Wave, procedural_waves},
 };
 
+/// Scores at which the enemy fire interval shrinks by `ENEMY_FIRE_SPEEDUP`.
+const ENEMY_FIRE_BREAKPOINTS: [u32; 7] = [10, 25, 50, 75, 100, 150, 200];
+const ENEMY_FIRE_SPEEDUP: f32 = 0.8;
+const SPAWN_ATTEMPTS: usize = 5;
+const SPAWN_PLAYER_CLEARANCE: f32 = 200.0;
+const SPAWN_BOTTOM_MARGIN: f32 = 250.0;
+const FORMATION_ROWS: u32 = 3;
+const FORMATION_COLUMNS: u32 = 6;
+const FORMATION_SPACING: Vec2 = Vec2::new(90.0, 60.0);
+const FORMATION_STEP: f32 = 15.0;
+const FORMATION_STEP_SECONDS: f32 = 0.5;
+const FORMATION_DROP: f32 = 30.0;
+const FORMATION_EDGE_MARGIN: f32 = 50.0;
+const BERSERK_TINT: Color = Color::srgb(1.0, 0.3, 0.3);
+const HOMING_SLOTS: u32 = 5;
+const HOMING_SLOT_SPACING: f32 = 60.0;
+const ARMORED_TINT: Color = Color::srgb(0.7, 0.8, 1.0);
+const MEDIC_TINT: Color = Color::srgb(0.4, 1.0, 0.5);
+const SUPPORT_TINT: Color = Color::srgb(0.4, 0.8, 1.0);
+const BOMBER_TINT: Color = Color::srgb(1.0, 0.8, 0.2);
+/// Bombers dive this many times faster than enemies in `EnemyMovement::Descend`.
+const BOMBER_DIVE_SPEEDUP: f32 = 3.0;
+const MIMIC_TINT: Color = Color::srgb(0.8, 0.5, 1.0);
+/// Enemies closer than this to a support enemy are protected by it.
+const SUPPORT_RADIUS: f32 = 120.0;
+const BOSS_TINT: Color = Color::srgb(1.0, 0.5, 0.3);
+/// Boss sprite and hitbox size relative to a normal enemy.
+const BOSS_SIZE: f32 = 2.5;
+const BOSS_HEALTH: u32 = 25;
+/// Warning players get between an enemy lighting up and its lasers leaving.
+const ENEMY_CHARGE_SECONDS: f32 = 0.3;
+const CHARGE_COLOR: Color = Color::srgb(1.0, 0.3, 0.2);
+const CHARGE_INDICATOR_SIZE: f32 = 24.0;
+/// How long a new enemy takes to fade and scale in.
+const ENEMY_SPAWN_SECONDS: f32 = 0.4;
+/// Enemy laser speed gained per wave, up to `ENEMY_LASER_WAVE_SPEED_MAX`.
+const ENEMY_LASER_WAVE_SPEEDUP: f32 = 0.05;
+const ENEMY_LASER_WAVE_SPEED_MAX: f32 = 1.6;
+/// Random spread on each laser's speed, as a share of it.
+const ENEMY_LASER_SPEED_VARIANCE: f32 = 0.1;
+const SNIPER_CHANCE: f64 = 0.08;
+const SNIPER_SPEEDUP: f32 = 1.75;
+/// Snipers are drawn thinner and longer than a normal laser.
+const SNIPER_SCALE: Vec2 = Vec2::new(0.6, 1.5);
+const SNIPER_COLOR: Color = Color::srgb(1.0, 0.3, 0.9);
+/// Hard cap on enemy laser speed. `movement` steps at most 1/30 s, and at this speed a
+/// sniper still moves less per step than its own length plus the smallest player
+/// hitbox, so it can't skip over the ship.
+const ENEMY_LASER_MAX_SPEED: f32 = 2.8;
+/// How long a new enemy takes to fly in to its slot.
+const ENEMY_ENTRY_SECONDS: f32 = 1.2;
+/// Extra delay per formation column, so a grid streams in rather than arriving at once.
+const FORMATION_ENTRY_STAGGER: f32 = 0.08;
+/// Distance beyond the screen edge that entering enemies start from.
+const ENEMY_ENTRY_MARGIN: f32 = 100.0;
+
+#[derive(Resource, Deref, DerefMut)]
+pub struct EnemyFireTimer(Timer);
+
+impl FromWorld for EnemyFireTimer {
+    fn from_world(world: &mut World) -> Self {
+        let seconds = world.resource::<GameConfig>().enemy_fire_interval;
+        Self(Timer::from_seconds(seconds, TimerMode::Repeating))
+    }
+}
+
+/// Marching state shared by every enemy in `EnemyMovement::Formation`.
+#[derive(Resource)]
+struct Formation {
+    /// 1.0 while marching right, -1.0 while marching left.
+    direction: f32,
+    step_timer: Timer,
+}
+
+impl Default for Formation {
+    fn default() -> Self {
+        Self {
+            direction: 1.0,
+            step_timer: Timer::from_seconds(FORMATION_STEP_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Seconds between enemy volleys for the given score.
+fn enemy_fire_interval(score: u32, config: &GameConfig) -> f32 {
+    let reached = ENEMY_FIRE_BREAKPOINTS
+        .iter()
+        .filter(|breakpoint| score >= **breakpoint)
+        .count();
+    (config.enemy_fire_interval * ENEMY_FIRE_SPEEDUP.powi(reached as i32))
+        .max(config.enemy_min_fire_interval)
+}
+
 pub struct EnemyPlugin;
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            enemy_spawn.run_if(on_timer(Duration::from_secs_f64(1.0))),
-        )
-        .add_systems(Update, enemy_move)
-        .add_systems(
-            Update,
-            enemy_fire.run_if(on_timer(Duration::from_secs_f64(1.0))),
-        );
+        app.init_resource::<EnemyFireTimer>()
+            .init_resource::<Formation>()
+            .add_systems(
+                Update,
+                enemy_spawn
+                    .run_if(on_timer(Duration::from_secs_f64(1.0)))
+                    .run_if(procedural_waves)
+                    .run_if(resource_equals(BossState::Absent)),
+            )
+            .add_systems(Update, (enemy_move, enemy_mimic).chain())
+            .add_systems(Update, enemy_cloak)
+            .add_systems(Update, enemy_spawn_anim)
+            .add_systems(Update, enemy_enter)
+            .add_systems(Update, enemy_berserk.run_if(in_state(GameState::Playing)))
+            .add_systems(Update, enemy_protect)
+            .add_systems(
+                Update,
+                enemy_cross_defense_line
+                    .run_if(in_state(GameState::Playing).and(resource_equals(DefenseLine(true)))),
+            )
+            .add_systems(Update, (enemy_fire_rate, enemy_charge, enemy_fire).chain());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemyKind {
+    Basic,
+    Cloaker,
+    Armored,
+    /// Drops a shield pickup when destroyed.
+    Medic,
+    /// Protects the enemies around it until it is destroyed.
+    Support,
+    /// Dives for the bottom of the screen and blows up there, see `BomberDetonated`.
+    Bomber,
+    /// Moves sideways with the player, see `enemy_mimic`.
+    Mimic,
+    /// Large enemy that takes many hits, announced by `BossState`.
+    Boss,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_enemy<'a>(
+    commands: &'a mut Commands,
+    game_textures: &GameTextures,
+    config: &GameConfig,
+    palette: Palette,
+    difficulty: Difficulty,
+    rng: &mut GameRng,
+    kind: EnemyKind,
+    position: Vec2,
+) -> EntityCommands<'a> {
+    let mut sprite = Sprite::from_image(game_textures.enemy.clone());
+    sprite.color = match kind {
+        EnemyKind::Armored => ARMORED_TINT,
+        EnemyKind::Medic => MEDIC_TINT,
+        EnemyKind::Support => SUPPORT_TINT,
+        EnemyKind::Bomber => BOMBER_TINT,
+        EnemyKind::Mimic => MIMIC_TINT,
+        EnemyKind::Boss => BOSS_TINT,
+        _ => palette.enemy(),
+    };
+    let size = if kind == EnemyKind::Boss {
+        config.enemy_size * BOSS_SIZE
+    } else {
+        config.enemy_size
+    };
+    sprite.custom_size = Some(size);
+    // fades in from here in `enemy_spawn_anim`
+    sprite.color.set_alpha(0.0);
+
+    let mut entity = commands.spawn((
+        sprite,
+        Transform {
+            translation: position.extend(10.0),
+            scale: Vec3::new(0.0, 0.0, 1.),
+            ..Default::default()
+        },
+    ));
+    entity
+        .insert((
+            Spawning,
+            SpawnAnim {
+                timer: Timer::from_seconds(ENEMY_SPAWN_SECONDS, TimerMode::Once),
+            },
+        ))
+        .insert(SpriteSize(size))
+        .insert(Hitbox(ENEMY_HITBOX_SCALE * difficulty.enemy_hitbox_scale()))
+        .insert(Velocity { x: 0.0, y: 0.0 })
+        .insert(Movable { auto_despawn: true })
+        .insert(Enemy);
+
+    match kind {
+        EnemyKind::Basic => {
+            entity.insert(FirePattern::Twin);
+        }
+        EnemyKind::Cloaker => {
+            // ambushers take aimed shots the moment they reappear
+            entity.insert((
+                FirePattern::Aimed,
+                Cloak {
+                    timer: Timer::from_seconds(CLOAK_VISIBLE_SECONDS, TimerMode::Once),
+                    cloaked: false,
+                },
+                Uncloaked,
+            ));
+        }
+        EnemyKind::Armored => {
+            entity.insert((
+                FrontArmor {
+                    facing: Vec2::NEG_Y,
+                },
+                FirePattern::Spread,
+            ));
+        }
+        EnemyKind::Medic => {
+            entity.insert((FirePattern::Twin, DropsShield));
+        }
+        EnemyKind::Support => {
+            entity.insert((FirePattern::Twin, Support));
+        }
+        EnemyKind::Bomber => {
+            entity.insert((FirePattern::Twin, Bomber));
+        }
+        EnemyKind::Mimic => {
+            entity.insert((
+                FirePattern::Twin,
+                Mimic {
+                    mirror: rng.random_bool(0.5),
+                },
+            ));
+        }
+        EnemyKind::Boss => {
+            entity.insert((
+                FirePattern::Spread,
+                Boss {
+                    health: BOSS_HEALTH,
+                },
+            ));
+        }
+    }
+    entity
+}
+
+/// Where an enemy flying in to `target` starts: above the screen, on the side its slot
+/// is on.
+fn entry_start(target: Vec2, win_size: &WinSize) -> Vec2 {
+    let side = if target.x < 0.0 { -1.0 } else { 1.0 };
+    Vec2::new(
+        side * (win_size.w / 2.0 + ENEMY_ENTRY_MARGIN),
+        win_size.h / 2.0 + ENEMY_ENTRY_MARGIN,
+    )
+}
+
+fn entering(from: Vec2, target: Vec2, seconds: f32) -> Entering {
+    Entering {
+        from,
+        target,
+        timer: Timer::from_seconds(seconds, TimerMode::Once),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn enemy_spawn(
     mut commands: Commands,
     mut enemy_count: ResMut<EnemyCount>,
     max_enemies: Res<MaxEnemies>,
     game_textures: Res<GameTextures>,
     win_size: Res<WinSize>,
+    movement: Res<EnemyMovement>,
+    mut formation: ResMut<Formation>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    difficulty: Res<Difficulty>,
+    mutators: Res<Mutators>,
+    mut rng: ResMut<GameRng>,
+    player_query: Query<&Transform, With<Player>>,
 ) {
+    if *movement == EnemyMovement::Formation {
+        // a new grid marches in once the previous one is wiped out
+        if **enemy_count == 0 {
+            *formation = Formation::default();
+            let grid_w = (FORMATION_COLUMNS - 1) as f32 * FORMATION_SPACING.x;
+            let top = win_size.h / 2.0 - 100.0;
+            for row in 0..FORMATION_ROWS {
+                for column in 0..FORMATION_COLUMNS {
+                    let target = Vec2::new(
+                        -grid_w / 2.0 + column as f32 * FORMATION_SPACING.x,
+                        top - row as f32 * FORMATION_SPACING.y,
+                    );
+                    let from = entry_start(target, &win_size);
+                    let seconds = ENEMY_ENTRY_SECONDS + column as f32 * FORMATION_ENTRY_STAGGER;
+                    spawn_enemy(
+                        &mut commands,
+                        &game_textures,
+                        &config,
+                        *palette,
+                        *difficulty,
+                        &mut rng,
+                        EnemyKind::Basic,
+                        from,
+                    )
+                    .insert(entering(from, target, seconds));
+                }
+            }
+            **enemy_count = FORMATION_ROWS * FORMATION_COLUMNS;
+        }
+        return;
+    }
+
     if **enemy_count < **max_enemies {
-        let mut rng = rand::rng();
         let w_span = win_size.w / 2.0 - 100.0;
         let h_span = win_size.h / 2.0 - 100.0;
-        let x = rng.random_range(-w_span..w_span);
-        let y = rng.random_range(-h_span..h_span);
-        commands
+        let player = player_query
+            .single()
+            .ok()
+            .map(|tf| tf.translation.truncate());
+        // reroll positions that land next to the player, skipping this tick if none fit
+        let Some(position) = (0..SPAWN_ATTEMPTS)
+            .map(|_| {
+                Vec2::new(
+                    rng.random_range(-w_span..w_span),
+                    rng.random_range(-h_span..h_span),
+                )
+            })
+            .find(|position| spawn_allowed(*position, player, &win_size))
+        else {
+            return;
+        };
+        let special = mutators.special_enemies();
+        let kind = if special && rng.random_bool(config.cloak_chance) {
+            EnemyKind::Cloaker
+        } else if special && rng.random_bool(config.armored_chance) {
+            EnemyKind::Armored
+        } else if special && rng.random_bool(config.medic_chance) {
+            EnemyKind::Medic
+        } else if special && rng.random_bool(config.support_chance) {
+            EnemyKind::Support
+        } else if special && rng.random_bool(config.bomber_chance) {
+            EnemyKind::Bomber
+        } else if special && rng.random_bool(config.mimic_chance) {
+            EnemyKind::Mimic
+        } else {
+            EnemyKind::Basic
+        };
+        let from = entry_start(position, &win_size);
+        spawn_enemy(
+            &mut commands,
+            &game_textures,
+            &config,
+            *palette,
+            *difficulty,
+            &mut rng,
+            kind,
+            from,
+        )
+        .insert(entering(from, position, ENEMY_ENTRY_SECONDS));
+        **enemy_count += 1;
+    }
+}
+
+/// Whether an enemy may appear at `position`: away from the bottom of the screen and
+/// outside the no-spawn radius around the player.
+fn spawn_allowed(position: Vec2, player: Option<Vec2>, win_size: &WinSize) -> bool {
+    if position.y < -win_size.h / 2.0 + SPAWN_BOTTOM_MARGIN {
+        return false;
+    }
+    player.is_none_or(|player| position.distance(player) >= SPAWN_PLAYER_CLEARANCE)
+}
+
+/// Fades and scales new enemies in, and makes them hittable once they're whole.
+#[allow(clippy::type_complexity)]
+fn enemy_spawn_anim(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<
+        (
+            Entity,
+            &mut SpawnAnim,
+            &mut Sprite,
+            &mut Transform,
+            Has<Entering>,
+        ),
+        With<Enemy>,
+    >,
+) {
+    for (entity, mut anim, mut sprite, mut transform, entering) in &mut query {
+        anim.timer.tick(time.delta());
+        let progress = anim.timer.fraction();
+        sprite.color.set_alpha(progress);
+        transform.scale = Vec3::new(
+            config.sprite_scale * progress,
+            config.sprite_scale * progress,
+            1.0,
+        );
+
+        if anim.timer.finished() {
+            commands.entity(entity).remove::<SpawnAnim>();
+            // a flying enemy becomes hittable once it lands instead
+            if !entering {
+                commands.entity(entity).remove::<Spawning>();
+            }
+        }
+    }
+}
+
+/// Swings entering enemies in along a curve, easing into their slot, and hands them
+/// over to `enemy_move` once they arrive.
+fn enemy_enter(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Entering, &mut Transform), With<Enemy>>,
+) {
+    for (entity, mut entering, mut transform) in &mut query {
+        entering.timer.tick(time.delta());
+        let t = 1.0 - (1.0 - entering.timer.fraction()).powi(2);
+        // quadratic curve that sweeps across the top before dropping into the slot
+        let control = Vec2::new(entering.target.x, entering.from.y);
+        let position = entering
+            .from
+            .lerp(control, t)
+            .lerp(control.lerp(entering.target, t), t);
+        transform.translation = position.extend(transform.translation.z);
+
+        if entering.timer.finished() {
+            commands.entity(entity).remove::<(Entering, Spawning)>();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn enemy_cloak(
+    mut commands: Commands,
+    time: Res<Time>,
+    // the spawn fade owns the alpha until it's done
+    mut query: Query<(Entity, &mut Cloak, &mut Sprite), (With<Enemy>, Without<SpawnAnim>)>,
+) {
+    for (entity, mut cloak, mut sprite) in &mut query {
+        if cloak.timer.tick(time.delta()).just_finished() {
+            cloak.cloaked = !cloak.cloaked;
+            if cloak.cloaked {
+                cloak.timer = Timer::from_seconds(CLOAK_HIDDEN_SECONDS, TimerMode::Once);
+                commands.entity(entity).remove::<Uncloaked>();
+            } else {
+                cloak.timer = Timer::from_seconds(CLOAK_VISIBLE_SECONDS, TimerMode::Once);
+                commands.entity(entity).insert(Uncloaked);
+            }
+        }
+
+        let alpha = if cloak.cloaked {
+            // shimmer faintly, stronger as the decloak approaches
+            let shimmer = (time.elapsed_secs() * 20.0).sin().abs();
+            0.05 + 0.2 * shimmer * cloak.timer.fraction()
+        } else {
+            1.0
+        };
+        sprite.color.set_alpha(alpha);
+    }
+}
+
+fn enemy_fire_rate(
+    score: Res<Score>,
+    difficulty: Res<Difficulty>,
+    director: Res<Director>,
+    config: Res<GameConfig>,
+    mut fire_timer: ResMut<EnemyFireTimer>,
+) {
+    if score.is_changed() || difficulty.is_changed() || director.is_changed() {
+        let seconds = enemy_fire_interval(**score, &config)
+            * difficulty.fire_interval_scale()
+            * director.fire_interval_scale();
+        let interval = Duration::from_secs_f32(seconds);
+        fire_timer.set_duration(interval);
+    }
+}
+
+/// Starts charging every enemy whose volley comes up, showing a growing indicator
+/// beneath it until `enemy_fire` releases the shot.
+#[allow(clippy::type_complexity)]
+fn enemy_charge(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut fire_timer: ResMut<EnemyFireTimer>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, Option<&mut Berserk>, Has<FiringTimer>), (With<Enemy>, Exposed)>,
+) {
+    let volley = fire_timer.tick(time.delta()).just_finished();
+
+    for (entity, berserk, charging) in &mut query {
+        let fire = match berserk {
+            Some(mut berserk) => berserk.fire_timer.tick(time.delta()).just_finished(),
+            None => volley,
+        };
+        if !fire || charging {
+            continue;
+        }
+
+        let indicator = commands
             .spawn((
-                Sprite::from_image(game_textures.enemy.clone()),
-                Transform {
-                    translation: Vec3::new(x, y, 10.0),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                    ..Default::default()
-                },
+                Sprite::from_color(CHARGE_COLOR, Vec2::splat(CHARGE_INDICATOR_SIZE)),
+                // in the enemy's unscaled space, just below its hull
+                Transform::from_xyz(0.0, -config.enemy_size.y / 2.0, 1.0).with_scale(Vec3::ZERO),
+                ChildOf(entity),
             ))
-            .insert(SpriteSize::from(ENEMY_SIZE))
-            .insert(Velocity { x: 0.0, y: 0.0 })
-            .insert(Movable { auto_despawn: true })
-            .insert(Enemy);
-        **enemy_count += 1;
+            .id();
+        commands.entity(entity).insert(FiringTimer {
+            timer: Timer::from_seconds(ENEMY_CHARGE_SECONDS, TimerMode::Once),
+            indicator,
+        });
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn enemy_fire(
     mut commands: Commands,
+    time: Res<Time>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Enemy>>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    wave: Res<Wave>,
+    mut rng: ResMut<GameRng>,
+    mut query: Query<(Entity, &Transform, &FirePattern, &mut FiringTimer), With<Enemy>>,
+    mut indicator_query: Query<&mut Transform, (Without<Enemy>, Without<Player>)>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
 ) {
-    for enemy_tf in &query {
+    let player = player_query
+        .single()
+        .ok()
+        .map(|tf| tf.translation.truncate());
+
+    for (entity, enemy_tf, pattern, mut firing) in &mut query {
+        if !firing.timer.tick(time.delta()).finished() {
+            if let Ok(mut indicator_tf) = indicator_query.get_mut(firing.indicator) {
+                indicator_tf.scale = Vec3::splat(firing.timer.fraction());
+            }
+            continue;
+        }
+        commands.entity(firing.indicator).despawn();
+        commands.entity(entity).remove::<FiringTimer>();
+
         let (x, y) = (enemy_tf.translation.x, enemy_tf.translation.y);
-        let x_offset = ENEMY_SIZE.0 / 2. * SPRITE_SCALE - 25.;
+        let scale = config.sprite_scale;
+        let x_offset = config.enemy_size.x / 2. * scale - 25.;
+        let wave_speed = (1.0 + wave.number.saturating_sub(1) as f32 * ENEMY_LASER_WAVE_SPEEDUP)
+            .min(ENEMY_LASER_WAVE_SPEED_MAX);
+
+        let mut spawn_laser = |x_offset: f32, direction: Vec2| {
+            let sniper = rng.random_bool(SNIPER_CHANCE);
+            let variance =
+                1.0 + rng.random_range(-ENEMY_LASER_SPEED_VARIANCE..=ENEMY_LASER_SPEED_VARIANCE);
+            let mut speed = wave_speed * variance;
+            let (color, sprite_scale) = if sniper {
+                speed *= SNIPER_SPEEDUP;
+                (SNIPER_COLOR, SNIPER_SCALE * scale)
+            } else {
+                (palette.enemy_laser(), Vec2::splat(scale))
+            };
+            let velocity = direction * speed.min(ENEMY_LASER_MAX_SPEED);
 
-        let mut spawn_laser = |x_offset: f32| {
             commands
                 .spawn((
-                    Sprite::from_image(game_textures.enemy_laser.clone()),
+                    Sprite {
+                        color,
+                        ..Sprite::from_image(game_textures.enemy_laser.clone())
+                    },
                     Transform {
                         translation: Vec3::new(x + x_offset, y, 1.0),
-                        scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
-                        ..Default::default()
+                        // the sprite points down; turn it along its direction of travel
+                        rotation: Quat::from_rotation_z(Vec2::NEG_Y.angle_to(velocity)),
+                        scale: sprite_scale.extend(1.0),
                     },
                 ))
                 .insert(Laser)
                 .insert(FromEnemy)
-                .insert(SpriteSize::from(ENEMY_LASER_SIZE))
+                .insert(SpriteSize(config.enemy_laser_size))
                 .insert(Movable { auto_despawn: true })
-                .insert(Velocity { x: 0.0, y: -1.0 });
+                .insert(Lifetime::from_seconds(config.laser_lifetime))
+                .insert(Velocity {
+                    x: velocity.x,
+                    y: velocity.y,
+                });
         };
 
-        spawn_laser(x_offset);
-        spawn_laser(-x_offset);
+        match pattern {
+            FirePattern::Twin => {
+                spawn_laser(x_offset, Vec2::NEG_Y);
+                spawn_laser(-x_offset, Vec2::NEG_Y);
+            }
+            FirePattern::Spread => {
+                let count = config.enemy_spread_lasers.clamp(3, 5);
+                let first = -((count - 1) as f32) / 2.0 * config.enemy_spread_angle;
+                for index in 0..count {
+                    let angle = first + index as f32 * config.enemy_spread_angle;
+                    spawn_laser(0.0, Vec2::from_angle(angle).rotate(Vec2::NEG_Y));
+                }
+            }
+            FirePattern::Aimed => {
+                let direction = player
+                    .map(|player| {
+                        (player - enemy_tf.translation.truncate()).normalize_or(Vec2::NEG_Y)
+                    })
+                    .unwrap_or(Vec2::NEG_Y);
+                spawn_laser(0.0, direction);
+            }
+        }
+    }
+}
+
+/// Buffs the last enemy standing so clearing a wave doesn't stall, and lifts the buff
+/// again once reinforcements arrive.
+fn enemy_berserk(
+    mut commands: Commands,
+    fire_timer: Res<EnemyFireTimer>,
+    mut query: Query<(Entity, &mut Sprite, Option<&Berserk>), With<Enemy>>,
+) {
+    let last_one = query.iter().len() == 1;
+    for (entity, mut sprite, berserk) in &mut query {
+        match (last_one, berserk) {
+            (true, None) => {
+                let interval = fire_timer.duration().as_secs_f32() / BERSERK_FIRE_RATE;
+                commands.entity(entity).insert(Berserk {
+                    fire_timer: Timer::from_seconds(interval, TimerMode::Repeating),
+                    previous_color: sprite.color,
+                });
+                sprite.color = BERSERK_TINT.with_alpha(sprite.color.alpha());
+            }
+            (false, Some(berserk)) => {
+                sprite.color = berserk.previous_color.with_alpha(sprite.color.alpha());
+                commands.entity(entity).remove::<Berserk>();
+            }
+            _ => {}
+        }
     }
 }
 
-fn enemy_move(win_size: Res<WinSize>, mut query: Query<(&mut Velocity, &Transform), With<Enemy>>) {
-    for (mut velocity, transform) in &mut query {
-        let mut rng = rand::rng();
+/// Marks the enemies within reach of a support enemy as protected, and clears the mark
+/// from those no longer in reach, including once their support is destroyed.
+fn enemy_protect(
+    mut commands: Commands,
+    support_query: Query<(Entity, &Transform), With<Support>>,
+    query: Query<(Entity, &Transform, Has<Protected>), With<Enemy>>,
+) {
+    for (entity, transform, protected) in &query {
+        let position = transform.translation.truncate();
+        let in_reach = support_query.iter().any(|(support, support_tf)| {
+            support != entity
+                && support_tf.translation.truncate().distance(position) < SUPPORT_RADIUS
+        });
+        match (in_reach, protected) {
+            (true, false) => {
+                commands.entity(entity).insert(Protected);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Protected>();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn enemy_move(
+    mut commands: Commands,
+    time: Res<Time>,
+    win_size: Res<WinSize>,
+    config: Res<GameConfig>,
+    defense_line: Res<DefenseLine>,
+    movement: Res<EnemyMovement>,
+    mut formation: ResMut<Formation>,
+    player_query: Query<&Transform, With<Player>>,
+    mut rng: ResMut<GameRng>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    mut bomber_detonated: EventWriter<BomberDetonated>,
+    entering_query: Query<(), (With<Enemy>, With<Entering>)>,
+    mut query: Query<
+        (Entity, &mut Velocity, &mut Transform, Has<Bomber>),
+        (With<Enemy>, Without<Entering>),
+    >,
+) {
+    if *movement == EnemyMovement::Formation {
+        // the grid holds still until every enemy has reached its slot
+        if !entering_query.is_empty() {
+            return;
+        }
+        let step = formation.step_timer.tick(time.delta()).just_finished();
+        // the whole grid turns around as soon as any enemy reaches the edge
+        let edge = win_size.w / 2. - FORMATION_EDGE_MARGIN;
+        let at_edge = query
+            .iter()
+            .any(|(_, _, tf, _)| tf.translation.x * formation.direction >= edge);
+        if step && at_edge {
+            formation.direction = -formation.direction;
+        }
+
+        for (_, mut velocity, mut transform, _) in &mut query {
+            velocity.x = 0.;
+            velocity.y = 0.;
+            if !step {
+                continue;
+            }
+            if at_edge {
+                transform.translation.y -= FORMATION_DROP;
+            } else {
+                transform.translation.x += FORMATION_STEP * formation.direction;
+            }
+        }
+        return;
+    }
+
+    let player_x = player_query.single().ok().map(|tf| tf.translation.x);
+    let descending = *movement == EnemyMovement::Descend;
+
+    for (entity, mut velocity, transform, bomber) in &mut query {
         let x = rng.random_range(-0.02..=0.02);
         let y = rng.random_range(-0.02..=0.02);
 
@@ -94,17 +743,202 @@ fn enemy_move(win_size: Res<WinSize>, mut query: Query<(&mut Velocity, &Transfor
         velocity.y += y;
 
         let translation = transform.translation;
-        if translation.x < -win_size.w / 2. - 50. {
+        let bottom = -win_size.h / 2.;
+        if bomber {
+            // blows up on reaching the bottom instead of slipping away
+            if translation.y < bottom {
+                commands.entity(entity).despawn();
+                let position = Vec3::new(translation.x, bottom, translation.z);
+                spawn_explosion.write(SpawnExplosion::large(position));
+                bomber_detonated.write(BomberDetonated(position));
+                continue;
+            }
+            velocity.y = velocity.y.min(-ENEMY_DESCENT_SPEED * BOMBER_DIVE_SPEEDUP);
+        } else if descending {
+            velocity.y = velocity.y.min(-ENEMY_DESCENT_SPEED);
+            if let Some(player_x) = player_x {
+                velocity.x = homing_velocity(entity, translation.x, player_x, win_size.w);
+            }
+        }
+
+        let bound = config.enemy_bound_margin;
+        if translation.x < -win_size.w / 2. - bound {
             velocity.x = 0.3;
         }
-        if translation.x > win_size.w / 2. + 50. {
+        if translation.x > win_size.w / 2. + bound {
             velocity.x = -0.3;
         }
-        if translation.y < -win_size.h / 2. + 200. {
+        // let enemies drift down towards the defense line when it is active, and let
+        // descending enemies leave through the bottom
+        if translation.y < bottom + 200. && !**defense_line && !descending && !bomber {
             velocity.y = 0.3;
         }
-        if translation.y > win_size.h / 2. + 50. {
+        if translation.y > win_size.h / 2. + bound {
             velocity.y = -0.3;
         }
     }
 }
+
+/// Sets each mimic's sideways velocity from the player's, copied or mirrored. Mimics
+/// stop at the screen edge rather than follow the player off it.
+#[allow(clippy::type_complexity)]
+fn enemy_mimic(
+    win_size: Res<WinSize>,
+    movement: Res<EnemyMovement>,
+    player_query: Query<&Velocity, (With<Player>, Without<Enemy>)>,
+    mut query: Query<(&Mimic, &mut Velocity, &Transform, &SpriteSize), Without<Entering>>,
+) {
+    // the formation marches as one
+    if *movement == EnemyMovement::Formation {
+        return;
+    }
+    let player_x = player_query.single().map_or(0.0, |velocity| velocity.x);
+
+    for (mimic, mut velocity, transform, size) in &mut query {
+        velocity.x = if mimic.mirror { -player_x } else { player_x };
+        let edge = win_size.w / 2. - size.0.x * transform.scale.x / 2.;
+        let x = transform.translation.x;
+        if x.abs() > edge && velocity.x * x > 0.0 {
+            velocity.x = 0.0;
+        }
+    }
+}
+
+/// Horizontal velocity steering an enemy towards the player. Each enemy aims at its
+/// own slot around the player so a group spreads out instead of stacking on one x.
+fn homing_velocity(entity: Entity, enemy_x: f32, player_x: f32, win_w: f32) -> f32 {
+    let slot = (entity.index() % HOMING_SLOTS) as f32 - (HOMING_SLOTS / 2) as f32;
+    let target_x = player_x + slot * HOMING_SLOT_SPACING;
+    ((target_x - enemy_x) / (win_w / 2.)).clamp(-1.0, 1.0) * ENEMY_HOMING_STRENGTH
+}
+
+pub(crate) fn enemy_cross_defense_line(
+    mut commands: Commands,
+    win_size: Res<WinSize>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut screen_shake: ResMut<ScreenShake>,
+) {
+    let line_y = -win_size.h / 2. + DEFENSE_LINE_OFFSET;
+    if !enemy_query.iter().any(|tf| tf.translation.y < line_y) {
+        return;
+    }
+
+    for (player_entity, player_tf) in &player_query {
+        commands.entity(player_entity).despawn();
+        spawn_explosion.write(SpawnExplosion::large(player_tf.translation));
+    }
+    screen_shake.start(PLAYER_DEATH_SHAKE.0, PLAYER_DEATH_SHAKE.1);
+    next_state.set(GameState::GameOver);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::components::{Bomber, Mimic};
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn fire_interval_starts_at_the_base_interval() {
+        let config = GameConfig::default();
+        assert_close(enemy_fire_interval(0, &config), config.enemy_fire_interval);
+    }
+
+    #[test]
+    fn fire_interval_speeds_up_at_each_breakpoint() {
+        // with no floor, every breakpoint reached takes off one more step
+        let config = GameConfig {
+            enemy_min_fire_interval: 0.0,
+            ..default()
+        };
+        for (reached, breakpoint) in ENEMY_FIRE_BREAKPOINTS.iter().enumerate() {
+            let expected = config.enemy_fire_interval * ENEMY_FIRE_SPEEDUP.powi(reached as i32 + 1);
+            assert_close(enemy_fire_interval(*breakpoint, &config), expected);
+            let before = config.enemy_fire_interval * ENEMY_FIRE_SPEEDUP.powi(reached as i32);
+            assert_close(enemy_fire_interval(*breakpoint - 1, &config), before);
+        }
+    }
+
+    #[test]
+    fn fire_interval_is_clamped_to_the_minimum() {
+        let config = GameConfig::default();
+        assert_close(
+            enemy_fire_interval(u32::MAX, &config),
+            config.enemy_min_fire_interval,
+        );
+    }
+
+    /// Kind and slot of every enemy spawned over `ticks` calls to `enemy_spawn`, in
+    /// spawn order.
+    fn spawns_for_seed(seed: u64, ticks: usize) -> Vec<(Vec2, String)> {
+        let mut app = App::new();
+        // built directly so `RUST_INVADERS_SEED` in the environment can't override it
+        app.insert_resource(GameRng {
+            seed,
+            fixed_seed: Some(seed),
+            next_seed: None,
+            rng: StdRng::seed_from_u64(seed),
+        })
+        .insert_resource(GameTextures::default())
+        .insert_resource(EnemyCount(0))
+        .insert_resource(MaxEnemies(u32::MAX))
+        .insert_resource(WinSize { w: 800.0, h: 800.0 })
+        .insert_resource(EnemyMovement::Descend)
+        .init_resource::<Formation>()
+        .insert_resource(Palette::default())
+        .insert_resource(Difficulty::default())
+        .init_resource::<Mutators>()
+        .insert_resource(GameConfig::default());
+        for _ in 0..ticks {
+            app.world_mut().run_system_once(enemy_spawn).unwrap();
+        }
+
+        let world = app.world_mut();
+        let mut spawns: Vec<(Entity, Vec2, String)> = world
+            .query::<(
+                Entity,
+                &Entering,
+                Has<Cloak>,
+                Has<FrontArmor>,
+                Has<DropsShield>,
+                Has<Support>,
+                Has<Bomber>,
+                Option<&Mimic>,
+            )>()
+            .iter(world)
+            .map(
+                |(entity, entering, cloaker, armored, medic, support, bomber, mimic)| {
+                    let kind = format!(
+                        "{cloaker} {armored} {medic} {support} {bomber} {:?}",
+                        mimic.map(|mimic| mimic.mirror)
+                    );
+                    (entity, entering.target, kind)
+                },
+            )
+            .collect();
+        spawns.sort_by_key(|(entity, _, _)| *entity);
+        spawns
+            .into_iter()
+            .map(|(_, target, kind)| (target, kind))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_enemies() {
+        let first = spawns_for_seed(42, 200);
+        assert_eq!(first.len(), 200);
+        assert_eq!(first, spawns_for_seed(42, 200));
+        assert_ne!(first, spawns_for_seed(43, 200));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::{Event, Vec3};
+
+/// An enemy was destroyed by the player, at the given position.
+#[derive(Event)]
+pub struct EnemyKilled(pub Vec3);
+
+/// The player fired a shot.
+#[derive(Event)]
+pub struct PlayerFired;
+
+/// The player was hit by an enemy laser.
+#[derive(Event)]
+pub struct PlayerHit;
+
+/// A bomber reached the bottom of the screen and blew up at the given position.
+#[derive(Event)]
+pub struct BomberDetonated(pub Vec3);
+
+/// A new wave has started.
+#[derive(Event)]
+pub struct WaveStarted(pub u32);
+
+/// Something blew up: plays the explosion animation at `position`, drawn at `scale`
+/// times the sprite sheet's size and holding each frame for `frame_duration` seconds.
+#[derive(Event)]
+pub struct SpawnExplosion {
+    pub position: Vec3,
+    pub scale: f32,
+    pub frame_duration: f32,
+}
+
+impl SpawnExplosion {
+    /// A quick pop for basic enemies.
+    pub fn small(position: Vec3) -> Self {
+        Self {
+            position,
+            scale: 0.75,
+            frame_duration: 0.04,
+        }
+    }
+
+    /// The sprite sheet at its own size and speed.
+    pub fn medium(position: Vec3) -> Self {
+        Self {
+            position,
+            scale: 1.0,
+            frame_duration: 0.05,
+        }
+    }
+
+    /// A big, slow blast for the player and the boss.
+    pub fn large(position: Vec3) -> Self {
+        Self {
+            position,
+            scale: 2.0,
+            frame_duration: 0.08,
+        }
+    }
+}
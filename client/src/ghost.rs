@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ContinuedRun, GameState, GameTextures, Sandbox,
+    attract::AttractMode,
+    components::{Ghost, Player},
+    config::GameConfig,
+    game_over, get_data_path,
+    pause::Pause,
+    replay::Playback,
+    save::{load_json, save_json},
+    stats::RunStats,
+};
+
+/// Seconds between recorded positions; the ghost glides between them.
+const GHOST_SAMPLE_SECONDS: f32 = 0.1;
+const GHOST_ALPHA: f32 = 0.3;
+
+/// The ship's x position every `GHOST_SAMPLE_SECONDS` of a run. The best run's log is
+/// saved to `ghost.json` next to the high score.
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct PathLog {
+    samples: Vec<f32>,
+    #[serde(skip)]
+    elapsed: f32,
+}
+
+impl PathLog {
+    /// Position `elapsed` seconds into the run, or `None` once the run is over.
+    fn position(&self, elapsed: f32) -> Option<f32> {
+        let at = elapsed / GHOST_SAMPLE_SECONDS;
+        let index = at as usize;
+        let from = *self.samples.get(index)?;
+        let to = self.samples.get(index + 1).copied().unwrap_or(from);
+        Some(from.lerp(to, at.fract()))
+    }
+}
+
+/// The saved best run, played back by the ghost ship.
+#[derive(Resource, Default)]
+struct BestPath(PathLog);
+
+#[derive(Resource, Deref)]
+struct GhostPath(PathBuf);
+
+pub struct GhostPlugin;
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        let ghost_path = get_data_path("ghost.json").unwrap_or_default();
+
+        app.init_resource::<PathLog>()
+            .init_resource::<BestPath>()
+            .insert_resource(GhostPath(ghost_path))
+            .add_systems(OnEnter(GameState::Playing), ghost_start)
+            .add_systems(OnExit(GameState::Playing), ghost_despawn)
+            .add_systems(
+                Update,
+                (path_record, ghost_move).run_if(in_state(Pause::Running)),
+            )
+            // `game_over` decides whether this run set the high score
+            .add_systems(OnEnter(GameState::GameOver), path_save.after(game_over));
+    }
+}
+
+/// Loads the best run and puts its ghost on the field, unless the run is being
+/// continued, which carries on with the ghost and log it had.
+#[allow(clippy::too_many_arguments)]
+fn ghost_start(
+    mut commands: Commands,
+    continued: Res<ContinuedRun>,
+    ghost_path: Res<GhostPath>,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+    mut log: ResMut<PathLog>,
+    mut best: ResMut<BestPath>,
+    ghost_query: Query<Entity, With<Ghost>>,
+) {
+    if **continued {
+        return;
+    }
+    // a restart from the pause menu enters `Playing` again without leaving it
+    for entity in &ghost_query {
+        commands.entity(entity).despawn();
+    }
+    *log = PathLog::default();
+    best.0 = load_json(&ghost_path);
+    if best.0.samples.is_empty() {
+        return;
+    }
+
+    let scale = config.sprite_scale;
+    let mut sprite = Sprite::from_image(game_textures.player.clone());
+    sprite.color.set_alpha(GHOST_ALPHA);
+    commands.spawn((
+        sprite,
+        Transform {
+            translation: Vec3::new(best.0.samples[0], 0.0, 9.0),
+            scale: Vec3::new(scale, scale, 1.0),
+            ..default()
+        },
+        Ghost,
+    ));
+}
+
+fn ghost_despawn(mut commands: Commands, ghost_query: Query<Entity, With<Ghost>>) {
+    for entity in &ghost_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn path_record(
+    time: Res<Time>,
+    mut log: ResMut<PathLog>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    let due = (log.elapsed / GHOST_SAMPLE_SECONDS) as usize + 1;
+    if log.samples.len() < due {
+        log.samples.push(player_tf.translation.x);
+    }
+    log.elapsed += time.delta_secs();
+}
+
+/// Follows the best run's path at the player's height, and leaves where that run ended.
+fn ghost_move(
+    mut commands: Commands,
+    log: Res<PathLog>,
+    best: Res<BestPath>,
+    player_query: Query<&Transform, (With<Player>, Without<Ghost>)>,
+    mut ghost_query: Query<(Entity, &mut Transform), With<Ghost>>,
+) {
+    for (entity, mut transform) in &mut ghost_query {
+        let Some(x) = best.0.position(log.elapsed) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        transform.translation.x = x;
+        if let Ok(player_tf) = player_query.single() {
+            transform.translation.y = player_tf.translation.y;
+        }
+    }
+}
+
+fn path_save(
+    log: Res<PathLog>,
+    run_stats: Res<RunStats>,
+    sandbox: Res<Sandbox>,
+    attract: Res<AttractMode>,
+    playback: Res<Playback>,
+    ghost_path: Res<GhostPath>,
+) {
+    if !run_stats.new_high_score || **sandbox || **attract || playback.active() {
+        return;
+    }
+    let _ = save_json(&ghost_path, &*log);
+}
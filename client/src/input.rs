@@ -0,0 +1,383 @@
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    state::state::{EnterSchedules, StateTransition},
+    ui::UiSystem,
+    window::PrimaryWindow,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState, MouseControls, TouchControls,
+    components::{MainCamera, TouchButton},
+    get_config_path,
+    save::{load_json, save_json},
+    settings::RapidFire,
+};
+
+const GAMEPAD_DEADZONE: f32 = 0.2;
+const TOUCH_BUTTON_SIZE: f32 = 80.0;
+const TOUCH_BUTTON_MARGIN: f32 = 24.0;
+
+/// Keyboard keys for each player action, remappable through `key_bindings.json`.
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub fire: KeyCode,
+    pub missile: KeyCode,
+    pub slow_field: KeyCode,
+    pub bomb: KeyCode,
+    pub confirm: KeyCode,
+    pub pause: KeyCode,
+    pub restart: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_left: KeyCode::KeyA,
+            move_right: KeyCode::KeyD,
+            fire: KeyCode::ArrowUp,
+            missile: KeyCode::ArrowDown,
+            slow_field: KeyCode::KeyS,
+            bomb: KeyCode::Space,
+            confirm: KeyCode::Enter,
+            pause: KeyCode::Escape,
+            restart: KeyCode::KeyR,
+        }
+    }
+}
+
+/// How a key is written in on-screen prompts, e.g. `a` or `up-arrow`.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::ArrowUp => "up-arrow".to_string(),
+        KeyCode::ArrowDown => "down-arrow".to_string(),
+        KeyCode::ArrowLeft => "left-arrow".to_string(),
+        KeyCode::ArrowRight => "right-arrow".to_string(),
+        KeyCode::Escape => "esc".to_string(),
+        _ => {
+            let name = format!("{key:?}");
+            name.strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name)
+                .to_lowercase()
+        }
+    }
+}
+
+/// Player intent for the current frame, merged from every input device.
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerActions {
+    pub move_x: f32,
+    /// World x the ship should steer toward, when mouse controls are on.
+    pub aim_x: Option<f32>,
+    pub fire: bool,
+    /// Fire is held down on any device, whatever the rapid fire setting.
+    pub fire_held: bool,
+    pub missile: bool,
+    pub slow_field: bool,
+    pub bomb: bool,
+    pub confirm: bool,
+    pub pause: bool,
+    /// Restart the run from the pause menu. Missing from recordings made before it.
+    #[serde(default)]
+    pub restart: bool,
+}
+
+pub struct PlayerInputPlugin;
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        let bindings_path = get_config_path("key_bindings.json").unwrap_or_default();
+        let bindings: KeyBindings = load_json(&bindings_path);
+        // write the file out so players have something to edit
+        if !bindings_path.exists() {
+            let _ = save_json(&bindings_path, &bindings);
+        }
+
+        app.insert_resource(bindings)
+            .init_resource::<PlayerActions>()
+            .add_systems(Startup, touch_buttons_spawn)
+            .add_systems(
+                PreUpdate,
+                (read_player_actions, read_mouse_actions)
+                    .chain()
+                    .after(InputSystem)
+                    .after(UiSystem::Focus),
+            )
+            .add_systems(
+                StateTransition,
+                consume_actions_on_transition
+                    .after(EnterSchedules::<GameState>::default())
+                    .run_if(state_changed::<GameState>),
+            )
+            .add_systems(Update, touch_buttons_visibility);
+    }
+}
+
+fn read_player_actions(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    rapid_fire: Res<RapidFire>,
+    gamepads: Query<&Gamepad>,
+    touch_buttons: Query<(Ref<Interaction>, &TouchButton)>,
+    mut actions: ResMut<PlayerActions>,
+) {
+    let mut move_x = if keyboard.pressed(bindings.move_left) {
+        -1.0
+    } else if keyboard.pressed(bindings.move_right) {
+        1.0
+    } else {
+        0.0
+    };
+    // with rapid fire a held button keeps firing and the cooldown sets the pace
+    let mut fire = if **rapid_fire {
+        keyboard.pressed(bindings.fire)
+    } else {
+        keyboard.just_pressed(bindings.fire)
+    };
+    let mut fire_held = keyboard.pressed(bindings.fire);
+    let mut missile = keyboard.just_pressed(bindings.missile);
+    let mut slow_field = keyboard.just_pressed(bindings.slow_field);
+    let mut bomb = keyboard.just_pressed(bindings.bomb);
+    // edge-triggered so a held key can't carry through one menu into the next
+    let mut confirm = keyboard.just_pressed(bindings.confirm);
+    let mut pause = keyboard.just_pressed(bindings.pause);
+    let mut restart = keyboard.just_pressed(bindings.restart);
+
+    for gamepad in &gamepads {
+        if move_x == 0.0 {
+            let stick_x = gamepad.left_stick().x;
+            if gamepad.pressed(GamepadButton::DPadLeft) || stick_x < -GAMEPAD_DEADZONE {
+                move_x = -1.0;
+            } else if gamepad.pressed(GamepadButton::DPadRight) || stick_x > GAMEPAD_DEADZONE {
+                move_x = 1.0;
+            }
+        }
+        fire |= if **rapid_fire {
+            gamepad.pressed(GamepadButton::South)
+        } else {
+            gamepad.just_pressed(GamepadButton::South)
+        };
+        fire_held |= gamepad.pressed(GamepadButton::South);
+        missile |= gamepad.just_pressed(GamepadButton::East);
+        slow_field |= gamepad.just_pressed(GamepadButton::West);
+        bomb |= gamepad.just_pressed(GamepadButton::North);
+        confirm |= gamepad.just_pressed(GamepadButton::Start);
+        pause |= gamepad.just_pressed(GamepadButton::Select);
+        restart |= gamepad.just_pressed(GamepadButton::RightTrigger);
+    }
+
+    for (interaction, button) in &touch_buttons {
+        let pressed = *interaction == Interaction::Pressed;
+        if pressed && matches!(button, TouchButton::Fire) {
+            fire_held = true;
+        }
+        match button {
+            TouchButton::Left if pressed && move_x == 0.0 => move_x = -1.0,
+            TouchButton::Right if pressed && move_x == 0.0 => move_x = 1.0,
+            TouchButton::Fire if pressed && interaction.is_changed() => {
+                fire = true;
+                confirm = true;
+            }
+            TouchButton::Fire if pressed && **rapid_fire => fire = true,
+            _ => {}
+        }
+    }
+
+    *actions = PlayerActions {
+        move_x,
+        aim_x: None,
+        fire,
+        fire_held,
+        missile,
+        slow_field,
+        bomb,
+        confirm,
+        pause,
+        restart,
+    };
+}
+
+/// Drops the presses read this frame once the screen has changed under them, so one
+/// press can't act on the screen it left and again on the one it opened.
+fn consume_actions_on_transition(mut actions: ResMut<PlayerActions>) {
+    *actions = PlayerActions {
+        move_x: actions.move_x,
+        aim_x: actions.aim_x,
+        fire_held: actions.fire_held,
+        ..default()
+    };
+}
+
+/// Aims at the cursor and fires on left click, on top of the other devices.
+pub fn read_mouse_actions(
+    mouse_controls: Res<MouseControls>,
+    rapid_fire: Res<RapidFire>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut actions: ResMut<PlayerActions>,
+) {
+    if !**mouse_controls {
+        return;
+    }
+    actions.fire |= if **rapid_fire {
+        mouse.pressed(MouseButton::Left)
+    } else {
+        mouse.just_pressed(MouseButton::Left)
+    };
+    actions.fire_held |= mouse.pressed(MouseButton::Left);
+
+    let (Ok(window), Ok((camera, camera_tf))) = (window_query.single(), camera_query.single())
+    else {
+        return;
+    };
+    actions.aim_x = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_tf, cursor).ok())
+        .map(|position| position.x);
+}
+
+fn touch_buttons_spawn(mut commands: Commands) {
+    let buttons = [
+        (
+            TouchButton::Left,
+            "<",
+            Val::Px(TOUCH_BUTTON_MARGIN),
+            Val::Auto,
+        ),
+        (
+            TouchButton::Right,
+            ">",
+            Val::Px(TOUCH_BUTTON_MARGIN * 2.0 + TOUCH_BUTTON_SIZE),
+            Val::Auto,
+        ),
+        (
+            TouchButton::Fire,
+            "^",
+            Val::Auto,
+            Val::Px(TOUCH_BUTTON_MARGIN),
+        ),
+    ];
+
+    for (button, label, left, right) in buttons {
+        commands.spawn((
+            Button,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(TOUCH_BUTTON_MARGIN),
+                left,
+                right,
+                width: Val::Px(TOUCH_BUTTON_SIZE),
+                height: Val::Px(TOUCH_BUTTON_SIZE),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.15)),
+            Visibility::Hidden,
+            button,
+            children![Text::new(label)],
+        ));
+    }
+}
+
+/// Shows the touch buttons when forced on in the options, or automatically after a
+/// touch until a keyboard or gamepad is used again.
+fn touch_buttons_visibility(
+    touch_controls: Res<TouchControls>,
+    touches: Res<Touches>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut touch_active: Local<bool>,
+    mut query: Query<&mut Visibility, With<TouchButton>>,
+) {
+    if touches.any_just_pressed() {
+        *touch_active = true;
+    }
+    let gamepad_used = gamepads
+        .iter()
+        .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+    if keyboard.get_just_pressed().next().is_some() || gamepad_used {
+        *touch_active = false;
+    }
+
+    let visible = **touch_controls || *touch_active;
+    for mut visibility in &mut query {
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        input::{
+            ButtonState, InputPlugin,
+            keyboard::{Key, KeyboardInput},
+        },
+        state::app::StatesPlugin,
+    };
+
+    use super::*;
+    use crate::leave_results;
+
+    #[test]
+    fn key_names_match_the_prompts() {
+        assert_eq!(key_name(KeyCode::KeyA), "a");
+        assert_eq!(key_name(KeyCode::Digit5), "5");
+        assert_eq!(key_name(KeyCode::ArrowUp), "up-arrow");
+        assert_eq!(key_name(KeyCode::Escape), "esc");
+        assert_eq!(key_name(KeyCode::Space), "space");
+    }
+
+    #[test]
+    fn enter_held_across_game_over_does_not_skip_the_results() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, InputPlugin))
+            .init_state::<GameState>()
+            .insert_resource(KeyBindings::default())
+            .init_resource::<RapidFire>()
+            .init_resource::<PlayerActions>()
+            // scheduled as in `PlayerInputPlugin`, minus the bindings file
+            .add_systems(PreUpdate, read_player_actions.after(InputSystem))
+            .add_systems(
+                StateTransition,
+                consume_actions_on_transition
+                    .after(EnterSchedules::<GameState>::default())
+                    .run_if(state_changed::<GameState>),
+            )
+            .add_systems(Update, leave_results.run_if(in_state(GameState::GameOver)));
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        app.update();
+
+        // Enter goes down on the very frame the run ends, and stays down
+        app.world_mut().send_event(KeyboardInput {
+            key_code: KeyCode::Enter,
+            logical_key: Key::Enter,
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GameOver);
+        for _ in 0..5 {
+            app.update();
+            assert_eq!(
+                *app.world().resource::<State<GameState>>().get(),
+                GameState::GameOver
+            );
+        }
+    }
+}
@@ -2,50 +2,212 @@
 
 use std::{collections::HashSet, fs, io, path::PathBuf};
 
+use achievements::AchievementsPlugin;
+use asteroid::AsteroidPlugin;
+use attract::AttractPlugin;
 use bevy::{
+    ecs::system::SystemParam,
     math::bounding::{Aabb2d, IntersectsVolume},
     prelude::*,
-    window::PrimaryWindow,
+    render::view::RenderLayers,
+    tasks::Task,
+    window::{MonitorSelection, PrimaryWindow, WindowMode, WindowResized},
 };
+use bomb::{BombPlugin, Bombs};
+use boss::BossPlugin;
+use camera::{CameraPlugin, ScreenShake};
+use combo::{ComboPlugin, ComboState};
+use companion::CompanionPlugin;
 use components::{
-    Enemy, Explosion, ExplosionTimer, FromEnemy, FromPlayer, Laser, MainMenu, Movable, Player,
-    ScoreBoardUI, SpriteSize, Velocity,
+    Berserk, Boss, DefenseLineSprite, DropsShield, Enemy, Explosion, ExplosionTimer, Exposed,
+    FromEnemy, FromPlayer, FrontArmor, Hitbox, Invulnerable, Laser, Lifetime, MainCamera, MainMenu,
+    MenuOptions, Movable, Particle, Piercing, Player, Protected, ResultsScreen, ScoreBoardUI,
+    ScorePopup, Shield, Slowed, Spawning, SpriteSize, StatusUI, Velocity,
 };
+use config::GameConfig;
+use credits::{Credits, CreditsPlugin};
+use danger::DangerPlugin;
+use debug::DebugPlugin;
+use difficulty::{Difficulty, DifficultyPlugin};
+use director::DirectorPlugin;
 use directories::ProjectDirs;
 use enemy::EnemyPlugin;
-use player::PlayerPlugin;
+use events::{BomberDetonated, EnemyKilled, PlayerFired, PlayerHit, SpawnExplosion, WaveStarted};
+use ghost::GhostPlugin;
+use input::{KeyBindings, PlayerActions, PlayerInputPlugin, key_name};
+use menu::{MenuPlugin, menu_buttons};
+use missile::MissilePlugin;
+use mutators::{Mutators, MutatorsPlugin};
+use pause::{Pause, PausePlugin};
+use player::{ChargeState, PlayerPlugin, player_edge};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use replay::{Playback, ReplayPlugin};
+use rumble::RumblePlugin;
+use save::{load_high_score, load_json, save_high_score};
+use settings::SettingsPlugin;
+use shield::{ShieldPlugin, spawn_shield_pickup};
+use slow_field::{SlowFieldCharges, SlowFieldPlugin};
+use spatial::{GRID_CELL_SIZE, SpatialGrid};
+use stats::{RunStats, Stats, StatsPlugin};
+use targeting::TargetingPlugin;
+use tutorial::TutorialPlugin;
+use wave::WavePlugin;
 
+mod achievements;
+mod asteroid;
+mod attract;
+mod bomb;
+mod boss;
+mod camera;
+mod combo;
+mod companion;
 mod components;
+mod config;
+mod credits;
+mod danger;
+mod debug;
+mod difficulty;
+mod director;
 mod enemy;
+mod events;
+mod ghost;
+mod input;
+mod menu;
+mod missile;
+mod mutators;
+mod pause;
 mod player;
+mod replay;
+mod rumble;
+mod save;
+mod settings;
+mod shield;
+mod slow_field;
+mod spatial;
+mod stats;
+mod targeting;
+mod tutorial;
+mod wave;
 
 const PLAYER_SPRITE: &str = "player_a_01.png";
-const PLAYER_SIZE: (f32, f32) = (144., 75.);
 const PLAYER_LASER_SPRITE: &str = "laser_a_01.png";
 const PLAYER_LASER_UPGRADE: &str = "laser_green.png";
-const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
-const PLAYER_MAX_LASERS: usize = 10;
+const PLAYER_INVULNERABILITY: f32 = 2.0;
+const PLAYER_BLINK_INTERVAL: f32 = 0.1;
+/// Segments in the HUD's beam charge meter.
+const CHARGE_METER_LEN: usize = 10;
+/// Collision box as a share of the sprite. The player's is tight so lasers that
+/// visibly miss the wings don't count, while enemies stay generous.
+const PLAYER_HITBOX_SCALE: f32 = 0.6;
+const ENEMY_HITBOX_SCALE: f32 = 1.0;
 
 const ENEMY_SPRITE: &str = "enemy_a_01.png";
-const ENEMY_SIZE: (f32, f32) = (144., 75.);
 const ENEMY_LASER_SPRITE: &str = "laser_b_01.png";
-const ENEMY_LASER_SIZE: (f32, f32) = (17., 55.);
+const CLOAK_VISIBLE_SECONDS: f32 = 2.5;
+const CLOAK_HIDDEN_SECONDS: f32 = 2.0;
+/// Movement and fire rate multipliers for the last enemy alive.
+const BERSERK_SPEED: f32 = 1.8;
+const BERSERK_FIRE_RATE: f32 = 2.5;
+/// Downward velocity of enemies in `EnemyMovement::Descend`.
+const ENEMY_DESCENT_SPEED: f32 = 0.05;
+/// Top horizontal velocity of enemies tracking the player.
+const ENEMY_HOMING_STRENGTH: f32 = 0.2;
+/// Half-angle (radians) around an armored enemy's facing that deflects lasers.
+const FRONT_ARMOR_ARC: f32 = 0.6;
+const SPARK_SECONDS: f32 = 0.15;
+const SHIELD_PING_COLOR: Color = Color::srgb(0.4, 0.8, 1.0);
+const SHIELD_PING_SIZE: f32 = 14.0;
+/// Distance within which a destroyed enemy takes its neighbours down with it.
+const CHAIN_RADIUS: f32 = 80.0;
+/// Horizontal reach of a bomber blowing up at the bottom of the screen.
+const BOMBER_BLAST_RADIUS: f32 = 120.0;
 
 const EXPLOSION_SHEET: &str = "explo_a_sheet.png";
 const EXPLOSION_LEN: usize = 16;
+/// Debris sprites thrown out by each explosion.
+const EXPLOSION_PARTICLES: usize = 8;
+const PARTICLE_SECONDS: f32 = 0.5;
+/// Top speed of debris, in the same units as `Velocity`.
+const PARTICLE_SPEED: f32 = 0.6;
+const SCORE_POPUP_SECONDS: f32 = 0.8;
+/// Units per second a score popup rises while it fades.
+const SCORE_POPUP_RISE: f32 = 40.0;
+/// How long a laser trail lingers, which sets the trail's length.
+const TRAIL_SECONDS: f32 = 0.12;
+const PLAYER_TRAIL_COLOR: Color = Color::srgb(0.5, 0.8, 1.0);
+const ENEMY_TRAIL_COLOR: Color = Color::srgb(1.0, 0.4, 0.3);
 
-const SPRITE_SCALE: f32 = 0.5;
-const BASE_SPEED: f32 = 600.0;
+const PLAYER_DEATH_SHAKE: (f32, f32) = (0.4, 12.0);
 
-const LASER_UPGRADE_SCORE: u32 = 50;
+const DEFENSE_LINE_OFFSET: f32 = 150.0;
+
+const SLOW_FIELD_FACTOR: f32 = 0.35;
+
+/// Background for a fresh run, then for each score upgrade reached. Kept dark so
+/// sprites read the same against all of them.
+const BACKGROUND_COLORS: [Color; 5] = [
+    Color::srgb(0.04, 0.04, 0.04),
+    Color::srgb(0.03, 0.04, 0.07),
+    Color::srgb(0.05, 0.03, 0.08),
+    Color::srgb(0.08, 0.03, 0.06),
+    Color::srgb(0.09, 0.04, 0.03),
+];
+/// Share of the remaining way to the next background covered per second.
+const BACKGROUND_SHIFT_RATE: f32 = 0.5;
+
+/// Longest step `movement` takes in one frame, so a hitch can't carry a laser clean
+/// through whatever it should have hit.
+const MAX_MOVEMENT_DELTA: f32 = 1.0 / 30.0;
 
 #[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default)]
-enum GameState {
+pub enum GameState {
     #[default]
     Startup,
     MainMenu,
     Playing,
     GameOver,
+    Settings,
+}
+
+/// Randomness for everything that shapes a run, reseeded as each run starts so a
+/// given seed replays the same spawns. Purely cosmetic effects keep using the thread
+/// rng, so toggling them doesn't shift the sequence.
+#[derive(Resource, Deref, DerefMut)]
+struct GameRng {
+    /// Seed of the current run.
+    seed: u64,
+    /// From `RUST_INVADERS_SEED`, else `rng_seed` in the config. Without one every run
+    /// draws a fresh seed.
+    fixed_seed: Option<u64>,
+    /// One-off seed for the next run, used to play back a recording.
+    next_seed: Option<u64>,
+    #[deref]
+    rng: StdRng,
+}
+
+impl GameRng {
+    fn new(config: &GameConfig) -> Self {
+        let fixed_seed = std::env::var("RUST_INVADERS_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .or(config.rng_seed);
+        let seed = fixed_seed.unwrap_or_else(|| rand::rng().random());
+        Self {
+            seed,
+            fixed_seed,
+            next_seed: None,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn reseed(&mut self) {
+        self.seed = self
+            .next_seed
+            .take()
+            .or(self.fixed_seed)
+            .unwrap_or_else(|| rand::rng().random());
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
 }
 
 #[derive(Resource)]
@@ -54,7 +216,7 @@ pub struct WinSize {
     pub h: f32,
 }
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 struct GameTextures {
     player: Handle<Image>,
     player_laser: Handle<Image>,
@@ -71,23 +233,110 @@ struct Score(u32);
 #[derive(Resource, Deref, DerefMut)]
 struct HighScore(u32);
 
+/// Score the current run is measured against on the HUD.
+#[derive(Resource, Deref, DerefMut)]
+struct TargetScore(Option<u32>);
+
 #[derive(Resource, Deref, DerefMut)]
 struct EnemyCount(u32);
 
+#[derive(Resource, Deref, DerefMut)]
+struct Lives(u32);
+
 #[derive(Resource, Deref, DerefMut)]
 struct MaxEnemies(u32);
 
 #[derive(Resource, Deref, DerefMut)]
 struct LaserUpgrage(bool);
 
+#[derive(Resource, Deref, DerefMut)]
+struct SpreadShot(bool);
+
+/// Player lasers pass through enemies instead of stopping at the first.
+#[derive(Resource, Deref, DerefMut)]
+struct PiercingShot(bool);
+
+/// Player lasers shoot down enemy lasers they touch.
+#[derive(Resource, Deref, DerefMut, PartialEq)]
+struct LaserIntercept(bool);
+
 #[derive(Resource, Deref)]
 struct HighScorePath(PathBuf);
 
-fn get_high_score_path() -> io::Result<PathBuf> {
+/// The last high score write, awaited before quitting so it isn't cut short.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct PendingHighScoreWrite(Option<Task<()>>);
+
+/// When enabled, the game ends as soon as an enemy crosses the defense line.
+#[derive(Resource, Deref, DerefMut, PartialEq)]
+struct DefenseLine(bool);
+
+/// How enemies move around the play area.
+#[derive(Resource, Clone, Copy, PartialEq, Eq)]
+enum EnemyMovement {
+    /// Random jitter around where they spawned.
+    Drift,
+    /// Slowly sink while tracking the player's x position.
+    Descend,
+    /// March side to side as one grid, dropping a row at each edge.
+    Formation,
+}
+
+impl EnemyMovement {
+    fn name(&self) -> &'static str {
+        match self {
+            EnemyMovement::Drift => "drift",
+            EnemyMovement::Descend => "descend",
+            EnemyMovement::Formation => "formation",
+        }
+    }
+}
+
+/// Set when a run carries on from the results screen on a credit rather than starting
+/// fresh, so per-run progress such as the wave is kept.
+#[derive(Resource, Deref, DerefMut)]
+struct ContinuedRun(bool);
+
+/// Practice mode: weapons ignore cooldowns and scores are not recorded.
+#[derive(Resource, Deref, DerefMut)]
+struct Sandbox(bool);
+
+/// Always show the on-screen touch buttons, not just after a touch.
+#[derive(Resource, Deref, DerefMut)]
+struct TouchControls(bool);
+
+/// Steer toward the mouse cursor and fire with the left button.
+#[derive(Resource, Deref, DerefMut)]
+struct MouseControls(bool);
+
+/// The player leaves one side of the screen and comes back on the other instead of
+/// stopping at the edge.
+#[derive(Resource, Deref, DerefMut)]
+struct WrapMode(bool);
+
+/// The ship speeds up and coasts to a stop instead of moving at full speed at once,
+/// and bounces off the screen edge.
+#[derive(Resource, Deref, DerefMut)]
+struct Momentum(bool);
+
+/// Flags in the HUD when the enemy cap is reached and spawning is on hold.
+#[derive(Resource, Deref, DerefMut)]
+struct CapIndicator(bool);
+
+/// How far past the screen edge auto-despawning entities may travel; `None` keeps them
+/// alive off-screen, which the F6 debug toggle uses for stress testing.
+#[derive(Resource, Deref, DerefMut)]
+struct DespawnMargin(Option<f32>);
+
+/// Tones down purely cosmetic effects.
+#[derive(Resource, Deref, DerefMut)]
+struct ReduceEffects(bool);
+
+fn get_data_path(file_name: &str) -> io::Result<PathBuf> {
     if let Some(proj_dirs) = ProjectDirs::from("com", "balestech", "rust_invaders") {
         let data_dir = proj_dirs.data_local_dir();
         fs::create_dir_all(data_dir)?;
-        return Ok(data_dir.join("high_score.txt"));
+        return Ok(data_dir.join(file_name));
     }
     Err(io::Error::new(
         io::ErrorKind::NotFound,
@@ -95,35 +344,104 @@ fn get_high_score_path() -> io::Result<PathBuf> {
     ))
 }
 
+fn get_config_path(file_name: &str) -> io::Result<PathBuf> {
+    if let Some(proj_dirs) = ProjectDirs::from("com", "balestech", "rust_invaders") {
+        let config_dir = proj_dirs.config_dir();
+        fs::create_dir_all(config_dir)?;
+        return Ok(config_dir.join(file_name));
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "Could not determine config directory",
+    ))
+}
+
 fn main() {
-    let high_score_path = get_high_score_path().unwrap_or_default();
-    let high_score: u32 = fs::read_to_string(&high_score_path)
-        .unwrap_or_default()
-        .parse()
-        .unwrap_or_default();
+    let config_path = get_config_path("game_config.json").unwrap_or_default();
+    let config: GameConfig = load_json(&config_path);
+    let primary_window = Window {
+        title: "Rust Invaders!".into(),
+        resolution: config.window_resolution.into(),
+        mode: config.window_mode.window_mode(),
+        ..Default::default()
+    };
+
+    let high_score_path = get_data_path("high_score.txt").unwrap_or_default();
 
     App::new()
-        .insert_resource(ClearColor(Color::srgb(0.04, 0.04, 0.04)))
-        .insert_resource(HighScore(high_score))
+        .insert_resource(ClearColor(BACKGROUND_COLORS[0]))
         .insert_resource(Score(0))
+        .insert_resource(TargetScore(None))
         .insert_resource(EnemyCount(0))
-        .insert_resource(MaxEnemies(3))
+        .insert_resource(Lives(config.player_lives))
+        .insert_resource(MaxEnemies(config.max_enemies))
         .insert_resource(LaserUpgrage(false))
+        .insert_resource(SpreadShot(false))
+        .insert_resource(PiercingShot(false))
+        .insert_resource(LaserIntercept(false))
+        .insert_resource(GameRng::new(&config))
         .insert_resource(HighScorePath(high_score_path))
+        .init_resource::<PendingHighScoreWrite>()
+        .insert_resource(DefenseLine(false))
+        .insert_resource(EnemyMovement::Descend)
+        .insert_resource(Sandbox(false))
+        .insert_resource(ContinuedRun(false))
+        .insert_resource(ReduceEffects(false))
+        .insert_resource(TouchControls(false))
+        .insert_resource(MouseControls(false))
+        .insert_resource(WrapMode(false))
+        .insert_resource(Momentum(false))
+        .insert_resource(CapIndicator(false))
+        .insert_resource(DespawnMargin(Some(config.despawn_margin)))
+        .insert_resource(config)
         .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Rust Invaders!".into(),
-                resolution: (800., 800.).into(),
-                ..Default::default()
-            }),
+            primary_window: Some(primary_window),
             ..Default::default()
         }))
+        .add_event::<EnemyKilled>()
+        .add_event::<PlayerFired>()
+        .add_event::<PlayerHit>()
+        .add_event::<WaveStarted>()
+        .add_event::<SpawnExplosion>()
+        .add_event::<BomberDetonated>()
+        .add_plugins(PlayerInputPlugin)
         .add_plugins(PlayerPlugin)
         .add_plugins(EnemyPlugin)
+        .add_plugins(WavePlugin)
+        .add_plugins(StatsPlugin)
+        .add_plugins(AchievementsPlugin)
+        .add_plugins(DangerPlugin)
+        .add_plugins(CameraPlugin)
+        .add_plugins(SlowFieldPlugin)
+        .add_plugins(DebugPlugin)
+        .add_plugins(DifficultyPlugin)
+        .add_plugins(DirectorPlugin)
+        .add_plugins(ComboPlugin)
+        .add_plugins(PausePlugin)
+        .add_plugins(BombPlugin)
+        .add_plugins(CompanionPlugin)
+        .add_plugins(MutatorsPlugin)
+        .add_plugins(MissilePlugin)
+        .add_plugins(AsteroidPlugin)
+        .add_plugins(MenuPlugin)
+        .add_plugins(CreditsPlugin)
+        .add_plugins(AttractPlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(ShieldPlugin)
+        .add_plugins(TargetingPlugin)
+        .add_plugins(BossPlugin)
+        .add_plugins(RumblePlugin)
+        .add_plugins(TutorialPlugin)
+        .add_plugins(GhostPlugin)
+        .add_systems(PreStartup, load_high_score_file)
         .add_systems(Startup, setup)
-        .add_systems(Update, game_over.run_if(in_state(GameState::GameOver)))
-        .add_systems(Update, start_game.run_if(in_state(GameState::MainMenu)))
+        .add_systems(OnEnter(GameState::Playing), rng_reseed)
+        .add_systems(OnEnter(GameState::GameOver), game_over)
+        .add_systems(Update, leave_results.run_if(in_state(GameState::GameOver)))
         .add_systems(Update, movement)
+        .add_systems(Update, lifetime_despawn)
+        .add_systems(Update, count_enemies)
         .add_systems(
             Update,
             player_laser_hit_enemy.run_if(in_state(GameState::Playing)),
@@ -132,39 +450,84 @@ fn main() {
             Update,
             enemy_laser_hit_player.run_if(in_state(GameState::Playing)),
         )
+        .add_systems(
+            Update,
+            player_laser_hit_enemy_laser
+                .run_if(in_state(GameState::Playing).and(resource_equals(LaserIntercept(true)))),
+        )
+        .add_systems(
+            Update,
+            enemy_ram_player.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            bomber_blast_hit_player.run_if(in_state(GameState::Playing)),
+        )
         .add_systems(
             Update,
             update_scoreboard.run_if(in_state(GameState::Playing)),
         )
-        .add_systems(Update, explosion_animation)
+        .add_systems(
+            Update,
+            background_shift.run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(Update, update_status_ui)
+        .add_systems(Update, (explosion_spawn, explosion_animation).chain())
+        .add_systems(Update, (explosion_particles, particle_fade))
+        .add_systems(Update, score_popup_animation)
+        // trails would pile up while paused, since nothing fades
+        .add_systems(Update, laser_trails.run_if(not(in_state(Pause::Paused))))
+        .add_systems(OnEnter(GameState::MainMenu), spawn_menu_options)
+        .add_systems(
+            Update,
+            (toggle_options, update_menu_options).run_if(in_state(GameState::MainMenu)),
+        )
+        .add_systems(Update, update_defense_line)
+        .add_systems(Update, toggle_fullscreen)
+        .add_systems(Update, window_resized.run_if(resource_exists::<WinSize>))
         .init_state::<GameState>()
         .run();
 }
 
+/// Reseeded on entering `Playing` rather than when the run is set up, so nothing
+/// still running on the menu can draw from the new sequence first. A continue keeps
+/// drawing from the sequence the run started with.
+fn rng_reseed(continued: Res<ContinuedRun>, mut rng: ResMut<GameRng>) {
+    if !**continued {
+        rng.reseed();
+    }
+}
+
+/// Runs after logging is up so problems with the file get reported.
+fn load_high_score_file(mut commands: Commands, high_score_path: Res<HighScorePath>) {
+    commands.insert_resource(HighScore(load_high_score(&high_score_path)));
+}
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
     query: Query<&Window, With<PrimaryWindow>>,
+    bindings: Res<KeyBindings>,
     mut next_state: ResMut<NextState<GameState>>,
-    high_score: Res<HighScore>,
 ) {
-    commands.spawn(Camera2d);
+    // gameplay camera, free to shake and post-process
+    commands.spawn((Camera2d, MainCamera));
 
+    // UI camera drawn on top of the scene; it renders no sprites so the HUD stays crisp
     commands.spawn((
-        Text::new(format!(
-            "New Game [enter]\n\n\nmove: [a] & [d]\nshoot: [up-arrow]\n\n\nHigh Score: {}",
-            **high_score
-        )),
-        Node {
-            position_type: PositionType::Absolute,
-            top: Val::Px(350.0),
-            left: Val::Px(300.0),
+        Camera2d,
+        Camera {
+            order: 1,
+            clear_color: ClearColorConfig::None,
             ..default()
         },
-        MainMenu,
+        RenderLayers::layer(1),
+        IsDefaultUiCamera,
     ));
 
+    commands.spawn(main_menu(&bindings));
+
     commands.spawn((
         Text::new("Score: "),
         Node {
@@ -174,7 +537,18 @@ fn setup(
             ..default()
         },
         ScoreBoardUI,
-        children![(TextSpan::default(),)],
+        children![(TextSpan::default(),), (TextSpan::default(),)],
+    ));
+
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        },
+        StatusUI,
     ));
 
     // capture window size
@@ -187,6 +561,17 @@ fn setup(
     let win_size = WinSize { w: win_w, h: win_h };
     commands.insert_resource(win_size);
 
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(1.0, 0.2, 0.2, 0.5),
+            custom_size: Some(Vec2::new(win_w, 2.0)),
+            ..Default::default()
+        },
+        Transform::from_xyz(0.0, -win_h / 2.0 + DEFENSE_LINE_OFFSET, 5.0),
+        Visibility::Hidden,
+        DefenseLineSprite,
+    ));
+
     // create explosion texture atlas
     let explosion_texture_handle = asset_server.load(EXPLOSION_SHEET);
     let explosion_texture_atlas =
@@ -207,231 +592,924 @@ fn setup(
     next_state.set(GameState::MainMenu);
 }
 
-fn start_game(
-    mut commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
-    main_menu_query: Query<Entity, With<MainMenu>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut score: ResMut<Score>,
-) {
-    if input.pressed(KeyCode::Enter) {
-        for entity in &main_menu_query {
-            commands.entity(entity).despawn();
+/// Everything that makes up a run, reset when a new one starts. Settings such as the
+/// difficulty or game modes are left alone.
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+struct RunReset<'w, 's> {
+    commands: Commands<'w, 's>,
+    score: ResMut<'w, Score>,
+    lives: ResMut<'w, Lives>,
+    max_enemies: ResMut<'w, MaxEnemies>,
+    laser_upgrade: ResMut<'w, LaserUpgrage>,
+    spread_shot: ResMut<'w, SpreadShot>,
+    piercing_shot: ResMut<'w, PiercingShot>,
+    laser_intercept: ResMut<'w, LaserIntercept>,
+    target_score: ResMut<'w, TargetScore>,
+    high_score: Res<'w, HighScore>,
+    difficulty: Res<'w, Difficulty>,
+    config: Res<'w, GameConfig>,
+    mutators: Res<'w, Mutators>,
+    run_stats: ResMut<'w, RunStats>,
+    credits: ResMut<'w, Credits>,
+    continued: ResMut<'w, ContinuedRun>,
+    clear_color: ResMut<'w, ClearColor>,
+    leftover_query: Query<'w, 's, Entity, Or<(With<Enemy>, With<Laser>)>>,
+    player_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut Transform,
+            &'static mut Hitbox,
+            &'static mut Visibility,
+        ),
+        With<Player>,
+    >,
+}
+
+impl RunReset<'_, '_> {
+    /// Puts score, lives, upgrades and the playfield back to a fresh run.
+    fn restart_run(&mut self) {
+        **self.score = 0;
+        **self.lives = self
+            .difficulty
+            .starting_lives(self.mutators.starting_lives(self.config.player_lives));
+        **self.max_enemies = self.mutators.max_enemies(
+            self.difficulty
+                .starting_max_enemies(self.config.max_enemies),
+        );
+        **self.laser_upgrade = false;
+        **self.spread_shot = false;
+        **self.piercing_shot = false;
+        **self.laser_intercept = false;
+        *self.run_stats = RunStats::default();
+        *self.credits = Credits::default();
+        **self.continued = false;
+        **self.target_score = (**self.high_score > 0).then_some(**self.high_score);
+        self.clear_color.0 = BACKGROUND_COLORS[0];
+        for entity in &self.leftover_query {
+            self.commands.entity(entity).despawn();
+        }
+        for (entity, mut transform, mut hitbox, mut visibility) in &mut self.player_query {
+            transform.translation.x = 0.0;
+            hitbox.0 = PLAYER_HITBOX_SCALE * self.difficulty.player_hitbox_scale();
+            // a restart mid-blink would otherwise carry the respawn grace into the new
+            // run, possibly with the ship hidden
+            self.commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
         }
-        **score = 0;
-        next_state.set(GameState::Playing);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn game_over(
     mut commands: Commands,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut max_enemies: ResMut<MaxEnemies>,
-    mut enemy_count: ResMut<EnemyCount>,
-    mut laser_velocity_upgrade: ResMut<LaserUpgrage>,
-    explosion_query: Query<(), With<Explosion>>,
-    enemy_query: Query<Entity, With<Enemy>>,
     score: Res<Score>,
     mut high_score: ResMut<HighScore>,
     high_score_path: Res<HighScorePath>,
+    mut pending_write: ResMut<PendingHighScoreWrite>,
+    sandbox: Res<Sandbox>,
+    playback: Res<Playback>,
+    mut run_stats: ResMut<RunStats>,
+    credits: Res<Credits>,
 ) {
-    // reset enemies & upgrades
-    **max_enemies = 3;
-    **laser_velocity_upgrade = false;
-    for entity in &enemy_query {
+    // check for new high score, sandbox runs and replays don't count
+    if **score > **high_score && !**sandbox && !playback.active() {
+        **high_score = **score;
+        run_stats.new_high_score = true;
+        **pending_write = Some(save_high_score(high_score_path.to_path_buf(), **high_score));
+    }
+
+    let new_high_score = if run_stats.new_high_score {
+        "New High Score!\n"
+    } else {
+        ""
+    };
+    let continue_prompt = if credits.available > 0 {
+        format!("continue [c] ({} credits)\n", credits.available)
+    } else {
+        String::new()
+    };
+    commands.spawn((
+        centered_menu(format!(
+            "You Died!\nGame Over\n\nScore: {}\n{}Enemies Destroyed: {}\nLongest Combo: {}\n\n{}main menu [enter]",
+            **score,
+            new_high_score,
+            run_stats.enemies_destroyed,
+            run_stats.longest_combo,
+            continue_prompt
+        )),
+        ResultsScreen,
+    ));
+}
+
+fn leave_results(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    bindings: Res<KeyBindings>,
+    mut next_state: ResMut<NextState<GameState>>,
+    results_query: Query<Entity, With<ResultsScreen>>,
+    enemy_query: Query<Entity, With<Enemy>>,
+) {
+    if !actions.confirm {
+        return;
+    }
+    for entity in results_query.iter().chain(&enemy_query) {
         commands.entity(entity).despawn();
-        **enemy_count -= 1;
     }
+    commands.spawn(main_menu(&bindings));
+    next_state.set(GameState::MainMenu);
+}
 
-    // wait for explosions to finish
-    if explosion_query.iter().len() == 0 {
-        // check for new high score
-        if **score > **high_score {
-            **high_score = **score;
-            let _ = fs::write(&**high_score_path, format!("{}", **high_score));
-        }
+/// The controls listed on the title screen, following the key bindings.
+fn controls_help(bindings: &KeyBindings) -> String {
+    format!(
+        "move: [{}] & [{}]\nshoot: [{}]\nmissile: [{}]\npause: [{}]\nachievements: [tab]\nreplay last run: [p]\nquit: [q]",
+        key_name(bindings.move_left),
+        key_name(bindings.move_right),
+        key_name(bindings.fire),
+        key_name(bindings.missile),
+        key_name(bindings.pause),
+    )
+}
 
-        commands.spawn((
-            Text::new(format!(
-                "You Died!\nGame Over\n\nrestart [enter]\n\n\nHigh Score: {}",
-                **high_score
-            )),
-            Node {
-                position_type: PositionType::Absolute,
-                top: Val::Px(350.0),
-                left: Val::Px(350.0),
-                ..default()
-            },
-            MainMenu,
-        ));
-        next_state.set(GameState::MainMenu);
+/// Title screen listing the controls above the menu buttons.
+fn main_menu(bindings: &KeyBindings) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            (
+                Text::new(format!("Rust Invaders\n\n{}", controls_help(bindings))),
+                TextLayout::new_with_justify(JustifyText::Center)
+            ),
+            menu_buttons(),
+        ],
+        MainMenu,
+    )
+}
+
+/// Lifetime totals shown on the leaderboard.
+fn stats_summary(stats: &Stats) -> String {
+    format!(
+        "Games Played: {}\nEnemies Destroyed: {}\nTotal Score: {}",
+        stats.games_played, stats.enemies_destroyed, stats.total_score
+    )
+}
+
+/// Menu text centered on the window whatever its size.
+fn centered_menu(text: String) -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![(
+            Text::new(text),
+            TextLayout::new_with_justify(JustifyText::Center)
+        )],
+    )
+}
+
+/// F11 flips between a window and borderless fullscreen. The resulting resize event
+/// updates `WinSize` through `window_resized`.
+fn toggle_fullscreen(
+    input: Res<ButtonInput<KeyCode>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !input.just_pressed(KeyCode::F11) {
+        return;
+    }
+    let Ok(mut window) = window_query.single_mut() else {
+        return;
+    };
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+    };
+}
+
+#[allow(clippy::type_complexity)]
+fn window_resized(
+    mut resize_events: EventReader<WindowResized>,
+    mut win_size: ResMut<WinSize>,
+    config: Res<GameConfig>,
+    mut defense_line_query: Query<
+        (&mut Transform, &mut Sprite),
+        (With<DefenseLineSprite>, Without<Player>),
+    >,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    let Some(event) = resize_events.read().last() else {
+        return;
+    };
+    win_size.w = event.width;
+    win_size.h = event.height;
+
+    for (mut transform, mut sprite) in &mut defense_line_query {
+        transform.translation.y = -win_size.h / 2.0 + DEFENSE_LINE_OFFSET;
+        sprite.custom_size = Some(Vec2::new(win_size.w, 2.0));
+    }
+
+    // keep the ship on the bottom edge and inside the new width
+    let edge = player_edge(&win_size, &config).max(0.0);
+    for mut transform in &mut player_query {
+        transform.translation.y =
+            -win_size.h / 2.0 + config.player_size.y / 2. * config.sprite_scale + 5.;
+        transform.translation.x = transform.translation.x.clamp(-edge, edge);
     }
 }
 
+fn spawn_menu_options(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+        MenuOptions,
+        MainMenu,
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn toggle_options(
+    input: Res<ButtonInput<KeyCode>>,
+    mut defense_line: ResMut<DefenseLine>,
+    mut enemy_movement: ResMut<EnemyMovement>,
+    mut sandbox: ResMut<Sandbox>,
+    mut reduce_effects: ResMut<ReduceEffects>,
+    mut touch_controls: ResMut<TouchControls>,
+    mut mouse_controls: ResMut<MouseControls>,
+    mut wrap_mode: ResMut<WrapMode>,
+    mut momentum: ResMut<Momentum>,
+    mut cap_indicator: ResMut<CapIndicator>,
+) {
+    if input.just_pressed(KeyCode::KeyL) {
+        **defense_line = !**defense_line;
+    }
+    if input.just_pressed(KeyCode::KeyM) {
+        *enemy_movement = match *enemy_movement {
+            EnemyMovement::Drift => EnemyMovement::Descend,
+            EnemyMovement::Descend => EnemyMovement::Formation,
+            EnemyMovement::Formation => EnemyMovement::Drift,
+        };
+    }
+    if input.just_pressed(KeyCode::KeyR) {
+        **reduce_effects = !**reduce_effects;
+    }
+    if input.just_pressed(KeyCode::KeyT) {
+        **touch_controls = !**touch_controls;
+    }
+    if input.just_pressed(KeyCode::KeyO) {
+        **mouse_controls = !**mouse_controls;
+    }
+    if input.just_pressed(KeyCode::KeyW) {
+        **wrap_mode = !**wrap_mode;
+    }
+    if input.just_pressed(KeyCode::KeyI) {
+        **momentum = !**momentum;
+    }
+    if input.just_pressed(KeyCode::KeyC) {
+        **cap_indicator = !**cap_indicator;
+    }
+    if input.just_pressed(KeyCode::F2) {
+        **sandbox = !**sandbox;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_menu_options(
+    defense_line: Res<DefenseLine>,
+    sandbox: Res<Sandbox>,
+    reduce_effects: Res<ReduceEffects>,
+    touch_controls: Res<TouchControls>,
+    mouse_controls: Res<MouseControls>,
+    wrap_mode: Res<WrapMode>,
+    momentum: Res<Momentum>,
+    difficulty: Res<Difficulty>,
+    enemy_movement: Res<EnemyMovement>,
+    cap_indicator: Res<CapIndicator>,
+    mut query: Query<&mut Text, With<MenuOptions>>,
+) {
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+    for mut text in &mut query {
+        text.0 = format!(
+            "difficulty [<-/->]: {}\nenemy movement [m]: {}\ndefense line [l]: {}\nreduce effects [r]: {}\ntouch controls [t]: {}\nmouse controls [o]: {}\nwrap around [w]: {}\nmomentum [i]: {}\ncap indicator [c]: {}\nsandbox [F2]: {}",
+            difficulty.name(),
+            enemy_movement.name(),
+            on_off(**defense_line),
+            on_off(**reduce_effects),
+            on_off(**touch_controls),
+            on_off(**mouse_controls),
+            on_off(**wrap_mode),
+            on_off(**momentum),
+            on_off(**cap_indicator),
+            on_off(**sandbox)
+        );
+    }
+}
+
+fn update_defense_line(
+    defense_line: Res<DefenseLine>,
+    mut query: Query<&mut Visibility, With<DefenseLineSprite>>,
+) {
+    if !defense_line.is_changed() {
+        return;
+    }
+    for mut visibility in &mut query {
+        *visibility = if **defense_line {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn update_scoreboard(
     score: Res<Score>,
+    target_score: Res<TargetScore>,
     mut laser_velocity_upgrade: ResMut<LaserUpgrage>,
-    mut max_enemies: ResMut<MaxEnemies>,
+    mut spread_shot: ResMut<SpreadShot>,
+    mut piercing_shot: ResMut<PiercingShot>,
+    mut laser_intercept: ResMut<LaserIntercept>,
+    config: Res<GameConfig>,
     score_root: Single<Entity, (With<ScoreBoardUI>, With<Text>)>,
     mut writer: TextUiWriter,
 ) {
     *writer.text(*score_root, 1) = score.to_string();
 
-    if **score == 5 {
-        **max_enemies = 10;
+    // compare against the target score, hidden when there is none
+    if let Some(target) = **target_score {
+        let delta = **score as i64 - target as i64;
+        *writer.text(*score_root, 2) = format!("   {:+} vs best", delta);
+        writer.color(*score_root, 2).0 = match delta {
+            d if d > 0 => Color::srgb(0.3, 1.0, 0.3),
+            d if d < 0 => Color::srgb(1.0, 0.3, 0.3),
+            _ => Color::WHITE,
+        };
+    } else {
+        writer.text(*score_root, 2).clear();
+    }
+
+    // combo multipliers can skip past a threshold, so unlock on reaching it
+    if **score >= config.spread_shot_score && !**spread_shot {
+        **spread_shot = true;
     }
-    if **score == LASER_UPGRADE_SCORE {
+    if **score >= config.laser_upgrade_score && !**laser_velocity_upgrade {
         **laser_velocity_upgrade = true;
     }
+    if **score >= config.piercing_score && !**piercing_shot {
+        **piercing_shot = true;
+    }
+    if **score >= config.laser_intercept_score && !**laser_intercept {
+        **laser_intercept = true;
+    }
 }
 
+/// Eases the background toward a slightly different tint for every score upgrade the
+/// run has reached.
+fn background_shift(
+    time: Res<Time>,
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let reached = [
+        config.max_enemies_upgrade_score,
+        config.spread_shot_score,
+        config.laser_upgrade_score,
+        config.piercing_score,
+    ]
+    .iter()
+    .filter(|threshold| **score >= **threshold)
+    .count();
+    let target = BACKGROUND_COLORS[reached].to_linear();
+    let current = clear_color.0.to_linear();
+    if current == target {
+        return;
+    }
+    let t = (BACKGROUND_SHIFT_RATE * time.delta_secs()).min(1.0);
+    clear_color.0 = current.mix(&target, t).into();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_status_ui(
+    difficulty: Res<Difficulty>,
+    lives: Res<Lives>,
+    slow_field_charges: Res<SlowFieldCharges>,
+    bombs: Res<Bombs>,
+    enemy_count: Res<EnemyCount>,
+    max_enemies: Res<MaxEnemies>,
+    cap_indicator: Res<CapIndicator>,
+    charge: Res<ChargeState>,
+    mut query: Query<&mut Text, With<StatusUI>>,
+) {
+    let cap_reached = **cap_indicator && **enemy_count >= **max_enemies;
+    for mut text in &mut query {
+        let mut status = format!(
+            "Mode: {}\nLives: {}\nSlow fields [s]: {}\nBombs [space]: {}",
+            difficulty.name(),
+            **lives,
+            **slow_field_charges,
+            **bombs
+        );
+        if cap_reached {
+            status.push_str("\nMAX");
+        }
+        if let Some(level) = charge.level() {
+            let filled = (level * CHARGE_METER_LEN as f32).round() as usize;
+            status.push_str(&format!(
+                "\nCharge: [{}{}]",
+                "#".repeat(filled),
+                "-".repeat(CHARGE_METER_LEN - filled)
+            ));
+        }
+        // avoid touching the text, and re-laying it out, when nothing changed
+        text.set_if_neq(Text(status));
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn movement(
     mut commands: Commands,
     win_size: Res<WinSize>,
-    mut enemy_count: ResMut<EnemyCount>,
-    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>,
-    enemy_query: Query<&Enemy>,
+    difficulty: Res<Difficulty>,
+    config: Res<GameConfig>,
+    despawn_margin: Res<DespawnMargin>,
+    mut query: Query<(
+        Entity,
+        &Velocity,
+        &mut Transform,
+        &Movable,
+        Has<Slowed>,
+        Has<Enemy>,
+        Has<Berserk>,
+    )>,
     time: Res<Time>,
 ) {
-    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+    for (entity, velocity, mut transform, movable, slowed, enemy, berserk) in query.iter_mut() {
         let translation = &mut transform.translation;
-        let delta = time.delta_secs();
-        translation.x += velocity.x * delta * BASE_SPEED;
-        translation.y += velocity.y * delta * BASE_SPEED;
+        let mut delta = time.delta_secs().min(MAX_MOVEMENT_DELTA);
+        if slowed {
+            delta *= SLOW_FIELD_FACTOR;
+        }
+        if enemy {
+            delta *= difficulty.enemy_speed_scale();
+        }
+        if berserk {
+            delta *= BERSERK_SPEED;
+        }
+        translation.x += velocity.x * delta * config.base_speed;
+        translation.y += velocity.y * delta * config.base_speed;
 
-        if movable.auto_despawn {
-            let margin = 200.0;
-            if translation.y > win_size.h / 2. + margin
+        if movable.auto_despawn
+            && let Some(margin) = **despawn_margin
+            && (translation.y > win_size.h / 2. + margin
                 || translation.y < -win_size.h / 2. - margin
                 || translation.x > win_size.w / 2. + margin
-                || translation.x < -win_size.w / 2. - margin
+                || translation.x < -win_size.w / 2. - margin)
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Keeps `EnemyCount` in sync with the enemies that actually exist, so despawns from
+/// any system can never make it drift or underflow.
+fn count_enemies(mut enemy_count: ResMut<EnemyCount>, enemy_query: Query<(), With<Enemy>>) {
+    **enemy_count = enemy_query.iter().len() as u32;
+}
+
+/// Half extents of an entity's collision box, shrunk by its `Hitbox` if it has one.
+fn hitbox_half_size(size: &SpriteSize, transform: &Transform, hitbox: Option<&Hitbox>) -> Vec2 {
+    let share = hitbox.map_or(1.0, |hitbox| hitbox.0);
+    size.half_extents(transform.scale.truncate()) * share
+}
+
+/// Whether a hit at `hit_offset` from an enemy's center lands on its armored front. The
+/// offset is measured relative to the enemy's size so the arc covers the same share of
+/// any sprite.
+fn hits_armor(facing: Vec2, hit_offset: Vec2, half_size: Vec2) -> bool {
+    let relative = (hit_offset / half_size).clamp(Vec2::splat(-1.0), Vec2::splat(1.0));
+    relative.angle_to(facing).abs() < FRONT_ARMOR_ARC
+}
+
+fn lifetime_despawn(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Lifetime)>,
+) {
+    for (entity, mut lifetime) in &mut query {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Player and enemy lasers that touch both go, leaving a spark where they met.
+#[allow(clippy::type_complexity)]
+fn player_laser_hit_enemy_laser(
+    mut commands: Commands,
+    player_laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
+    enemy_laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
+) {
+    let mut despawned_entities: HashSet<Entity> = HashSet::new();
+
+    let grid = SpatialGrid::from_boxes(
+        GRID_CELL_SIZE,
+        enemy_laser_query.iter().map(|(entity, tf, size)| {
+            let half_size = size.half_extents(tf.scale.truncate());
+            (entity, Aabb2d::new(tf.translation.truncate(), half_size))
+        }),
+    );
+
+    for (player_laser_entity, player_laser_tf, player_laser_size) in &player_laser_query {
+        let player_laser_box = Aabb2d::new(
+            player_laser_tf.translation.truncate(),
+            player_laser_size.half_extents(player_laser_tf.scale.truncate()),
+        );
+
+        for enemy_laser_entity in grid.candidates(&player_laser_box) {
+            if despawned_entities.contains(&enemy_laser_entity)
+                || despawned_entities.contains(&player_laser_entity)
             {
-                if enemy_query.get(entity).is_ok() {
-                    **enemy_count -= 1;
-                }
-                commands.entity(entity).despawn();
+                continue;
+            }
+            let Ok((_, enemy_laser_tf, enemy_laser_size)) =
+                enemy_laser_query.get(enemy_laser_entity)
+            else {
+                continue;
+            };
+
+            let collision = player_laser_box.intersects(&Aabb2d::new(
+                enemy_laser_tf.translation.truncate(),
+                enemy_laser_size.half_extents(enemy_laser_tf.scale.truncate()),
+            ));
+            if !collision {
+                continue;
             }
+
+            despawned_entities.insert(player_laser_entity);
+            despawned_entities.insert(enemy_laser_entity);
+            commands.entity(player_laser_entity).despawn();
+            commands.entity(enemy_laser_entity).despawn();
+            let midpoint = player_laser_tf
+                .translation
+                .truncate()
+                .midpoint(enemy_laser_tf.translation.truncate());
+            commands.spawn((
+                Sprite::from_color(Color::srgb(1.0, 0.9, 0.4), Vec2::splat(6.0)),
+                Transform::from_translation(midpoint.extend(20.0)),
+                Lifetime::from_seconds(SPARK_SECONDS),
+            ));
         }
     }
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn player_laser_hit_enemy(
     mut commands: Commands,
     mut score: ResMut<Score>,
-    mut enemy_count: ResMut<EnemyCount>,
-    game_textures: Res<GameTextures>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
-    enemy_query: Query<(Entity, &Transform, &SpriteSize), With<Enemy>>,
+    mut laser_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&mut Piercing>),
+        (With<Laser>, With<FromPlayer>),
+    >,
+    enemy_query: Query<
+        (
+            Entity,
+            &Transform,
+            &SpriteSize,
+            Option<&Hitbox>,
+            Option<&FrontArmor>,
+            Has<DropsShield>,
+            Has<Protected>,
+        ),
+        // bosses take several hits, see `boss_hit`
+        (With<Enemy>, Without<Boss>, Exposed),
+    >,
+    mut enemy_killed: EventWriter<EnemyKilled>,
+    mut spawn_explosion: EventWriter<SpawnExplosion>,
+    combo: Res<ComboState>,
+    mutators: Res<Mutators>,
 ) {
     let mut despawned_entities: HashSet<Entity> = HashSet::new();
 
-    for (laser_entity, laser_tf, laser_size) in &laser_query {
-        if despawned_entities.contains(&laser_entity) {
-            continue;
-        }
+    // bucket the enemies once so each laser is only tested against those around it
+    let grid = SpatialGrid::from_boxes(
+        GRID_CELL_SIZE,
+        enemy_query
+            .iter()
+            .map(|(entity, tf, size, hitbox, _, _, _)| {
+                let half_size = hitbox_half_size(size, tf, hitbox);
+                (entity, Aabb2d::new(tf.translation.truncate(), half_size))
+            }),
+    );
 
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
+    for (laser_entity, laser_tf, laser_size, mut piercing) in &mut laser_query {
+        let laser_box = Aabb2d::new(
+            laser_tf.translation.truncate(),
+            laser_size.half_extents(laser_tf.scale.truncate()),
+        );
 
-        for (enemy_entity, enemy_tf, enemy_size) in &enemy_query {
+        for enemy_entity in grid.candidates(&laser_box) {
             if despawned_entities.contains(&enemy_entity)
                 || despawned_entities.contains(&laser_entity)
             {
                 continue;
             }
+            let Ok((_, enemy_tf, enemy_size, hitbox, front_armor, _, protected)) =
+                enemy_query.get(enemy_entity)
+            else {
+                continue;
+            };
 
-            let enemy_scale = Vec2::from(enemy_tf.scale.xy());
-
-            let collision = Aabb2d::new(
-                laser_tf.translation.truncate(),
-                (laser_size.0 * laser_scale) / 2.0,
-            )
-            .intersects(&Aabb2d::new(
+            let enemy_half_size = hitbox_half_size(enemy_size, enemy_tf, hitbox);
+            let collision = laser_box.intersects(&Aabb2d::new(
                 enemy_tf.translation.truncate(),
-                (enemy_size.0 * enemy_scale) / 2.0,
+                enemy_half_size,
             ));
 
             if collision {
+                if piercing
+                    .as_ref()
+                    .is_some_and(|piercing| piercing.hit.contains(&enemy_entity))
+                {
+                    continue;
+                }
+                // a support enemy's shield stops any laser, piercing or not
+                if protected {
+                    despawned_entities.insert(laser_entity);
+                    commands.entity(laser_entity).despawn();
+                    commands.spawn((
+                        Sprite::from_color(SHIELD_PING_COLOR, Vec2::splat(SHIELD_PING_SIZE)),
+                        Transform::from_translation(laser_tf.translation.truncate().extend(20.0)),
+                        Lifetime::from_seconds(SPARK_SECONDS),
+                    ));
+                    continue;
+                }
+                if let Some(armor) = front_armor {
+                    let hit_offset =
+                        laser_tf.translation.truncate() - enemy_tf.translation.truncate();
+                    if hits_armor(armor.facing, hit_offset, enemy_half_size) {
+                        despawned_entities.insert(laser_entity);
+                        commands.entity(laser_entity).despawn();
+                        commands.spawn((
+                            Sprite::from_color(Color::srgb(1.0, 0.9, 0.4), Vec2::splat(6.0)),
+                            Transform::from_translation(
+                                (enemy_tf.translation.truncate()
+                                    + hit_offset.clamp(-enemy_half_size, enemy_half_size))
+                                .extend(20.0),
+                            ),
+                            Lifetime::from_seconds(SPARK_SECONDS),
+                        ));
+                        continue;
+                    }
+                }
+
+                match piercing.as_mut() {
+                    Some(piercing) => {
+                        piercing.hit.insert(enemy_entity);
+                    }
+                    None => {
+                        despawned_entities.insert(laser_entity);
+                        commands.entity(laser_entity).despawn();
+                    }
+                }
+
+                // each blast takes out enemies close by, which can set off more in turn
                 despawned_entities.insert(enemy_entity);
-                despawned_entities.insert(laser_entity);
-                commands.entity(enemy_entity).despawn();
-                commands.entity(laser_entity).despawn();
-                commands.spawn((
-                    Sprite {
-                        image: game_textures.explosion_texture.clone(),
-                        texture_atlas: Some(TextureAtlas {
-                            layout: game_textures.explosion_layout.clone(),
-                            index: 0,
-                        }),
-                        ..Default::default()
-                    },
-                    Transform::from_translation(enemy_tf.translation),
-                    Explosion,
-                    ExplosionTimer::default(),
-                ));
-                **score += 1;
-                enemy_count.0 -= 1;
+                let mut blasts = vec![enemy_entity];
+                while let Some(blast) = blasts.pop() {
+                    let Ok((_, blast_tf, _, _, _, drops_shield, _)) = enemy_query.get(blast) else {
+                        continue;
+                    };
+                    commands.entity(blast).despawn();
+                    spawn_explosion.write(SpawnExplosion::small(blast_tf.translation));
+                    let points = combo.multiplier() * mutators.score_multiplier();
+                    **score += points;
+                    enemy_killed.write(EnemyKilled(blast_tf.translation));
+                    commands.spawn((
+                        Text2d::new(format!("+{points}")),
+                        TextFont::from_font_size(18.0),
+                        Transform::from_translation(blast_tf.translation.truncate().extend(30.0)),
+                        ScorePopup::default(),
+                    ));
+                    if drops_shield {
+                        spawn_shield_pickup(&mut commands, blast_tf.translation);
+                    }
+
+                    let center = blast_tf.translation.truncate();
+                    let reach = Aabb2d::new(center, Vec2::splat(CHAIN_RADIUS));
+                    for other_entity in grid.candidates(&reach) {
+                        if despawned_entities.contains(&other_entity) {
+                            continue;
+                        }
+                        let Ok((_, other_tf, _, _, _, _, protected)) =
+                            enemy_query.get(other_entity)
+                        else {
+                            continue;
+                        };
+                        if protected {
+                            continue;
+                        }
+                        if other_tf.translation.truncate().distance(center) < CHAIN_RADIUS {
+                            despawned_entities.insert(other_entity);
+                            blasts.push(other_entity);
+                        }
+                    }
+                }
             }
         }
     }
 }
 
+/// Everything touched when the player takes a hit, shared by whatever can hurt them.
+#[derive(SystemParam)]
+struct PlayerDamage<'w, 's> {
+    commands: Commands<'w, 's>,
+    spawn_explosion: EventWriter<'w, SpawnExplosion>,
+    lives: ResMut<'w, Lives>,
+    next_state: ResMut<'w, NextState<GameState>>,
+    player_hit: EventWriter<'w, PlayerHit>,
+    screen_shake: ResMut<'w, ScreenShake>,
+    shield_query: Query<'w, 's, (), With<Shield>>,
+}
+
+impl PlayerDamage<'_, '_> {
+    /// Costs the player a life: respawns them at the center, briefly invulnerable, or
+    /// ends the run on the last one.
+    fn hit(&mut self, player_entity: Entity, player_tf: &Transform) {
+        if self.shield_query.contains(player_entity) {
+            self.commands
+                .entity(player_entity)
+                .remove::<Shield>()
+                .insert(Invulnerable::default());
+            return;
+        }
+        self.spawn_explosion
+            .write(SpawnExplosion::large(player_tf.translation));
+        self.player_hit.write(PlayerHit);
+        **self.lives = self.lives.saturating_sub(1);
+        if **self.lives == 0 {
+            self.commands.entity(player_entity).despawn();
+            self.screen_shake
+                .start(PLAYER_DEATH_SHAKE.0, PLAYER_DEATH_SHAKE.1);
+            self.next_state.set(GameState::GameOver);
+        } else {
+            let mut respawn_tf = *player_tf;
+            respawn_tf.translation.x = 0.0;
+            self.commands
+                .entity(player_entity)
+                .insert((respawn_tf, Invulnerable::default()));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn enemy_laser_hit_player(
     mut commands: Commands,
-    game_textures: Res<GameTextures>,
     laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
-    player_query: Query<(Entity, &Transform, &SpriteSize), With<Player>>,
-    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&Hitbox>),
+        (With<Player>, Without<Invulnerable>),
+    >,
+    mut damage: PlayerDamage,
 ) {
-    let mut despawned_entities: HashSet<Entity> = HashSet::new();
-
-    for (laser_entity, laser_tf, laser_size) in &laser_query {
-        if despawned_entities.contains(&laser_entity) {
-            continue;
-        }
+    // bucket the lasers so only those near the ship get the exact test
+    let grid = SpatialGrid::from_boxes(
+        GRID_CELL_SIZE,
+        laser_query.iter().map(|(entity, tf, size)| {
+            let half_size = size.half_extents(tf.scale.truncate());
+            (entity, Aabb2d::new(tf.translation.truncate(), half_size))
+        }),
+    );
 
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
+    for (player_entity, player_tf, player_size, hitbox) in &player_query {
+        let player_box = Aabb2d::new(
+            player_tf.translation.truncate(),
+            hitbox_half_size(player_size, player_tf, hitbox),
+        );
 
-        for (player_entity, player_tf, player_size) in &player_query {
-            if despawned_entities.contains(&player_entity) {
+        for laser_entity in grid.candidates(&player_box) {
+            let Ok((_, laser_tf, laser_size)) = laser_query.get(laser_entity) else {
                 continue;
-            }
-
-            let player_scale = Vec2::from(player_tf.scale.xy());
-
+            };
             let collision = Aabb2d::new(
                 laser_tf.translation.truncate(),
-                (laser_size.0 * laser_scale) / 2.0,
+                laser_size.half_extents(laser_tf.scale.truncate()),
             )
-            .intersects(&Aabb2d::new(
-                player_tf.translation.truncate(),
-                (player_size.0 * player_scale) / 2.0,
-            ));
+            .intersects(&player_box);
 
             if collision {
-                despawned_entities.insert(laser_entity);
-                despawned_entities.insert(player_entity);
                 commands.entity(laser_entity).despawn();
-                commands.entity(player_entity).despawn();
-                commands.spawn((
-                    Sprite {
-                        image: game_textures.explosion_texture.clone(),
-                        texture_atlas: Some(TextureAtlas {
-                            layout: game_textures.explosion_layout.clone(),
-                            index: 0,
-                        }),
-                        ..Default::default()
-                    },
-                    Transform::from_translation(player_tf.translation),
-                    Explosion,
-                    ExplosionTimer::default(),
-                ));
-                next_state.set(GameState::GameOver);
+                damage.hit(player_entity, player_tf);
                 break;
             }
         }
     }
 }
 
+/// An enemy flying into the ship blows up and takes a life with it. It earns no score.
+#[allow(clippy::type_complexity)]
+fn enemy_ram_player(
+    mut commands: Commands,
+    enemy_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&Hitbox>),
+        (With<Enemy>, Without<Spawning>),
+    >,
+    player_query: Query<
+        (Entity, &Transform, &SpriteSize, Option<&Hitbox>),
+        (With<Player>, Without<Invulnerable>),
+    >,
+    mut damage: PlayerDamage,
+) {
+    let Ok((player_entity, player_tf, player_size, player_hitbox)) = player_query.single() else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_tf.translation.truncate(),
+        hitbox_half_size(player_size, player_tf, player_hitbox),
+    );
+
+    for (enemy_entity, enemy_tf, enemy_size, enemy_hitbox) in &enemy_query {
+        let enemy_box = Aabb2d::new(
+            enemy_tf.translation.truncate(),
+            hitbox_half_size(enemy_size, enemy_tf, enemy_hitbox),
+        );
+        if !enemy_box.intersects(&player_box) {
+            continue;
+        }
+
+        commands.entity(enemy_entity).despawn();
+        damage
+            .spawn_explosion
+            .write(SpawnExplosion::small(enemy_tf.translation));
+        damage.hit(player_entity, player_tf);
+        break;
+    }
+}
+
+/// A bomber blowing up at the bottom of the screen takes a life if the ship is under it.
+#[allow(clippy::type_complexity)]
+fn bomber_blast_hit_player(
+    mut bomber_detonated: EventReader<BomberDetonated>,
+    player_query: Query<(Entity, &Transform), (With<Player>, Without<Invulnerable>)>,
+    mut damage: PlayerDamage,
+) {
+    let Ok((player_entity, player_tf)) = player_query.single() else {
+        bomber_detonated.clear();
+        return;
+    };
+    for BomberDetonated(position) in bomber_detonated.read() {
+        if (player_tf.translation.x - position.x).abs() < BOMBER_BLAST_RADIUS {
+            damage.hit(player_entity, player_tf);
+            break;
+        }
+    }
+    // a hit makes the ship invulnerable, so the rest of this frame's blasts are spent
+    bomber_detonated.clear();
+}
+
+/// The one place explosions are put on the field, whatever set them off.
+fn explosion_spawn(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    mut spawn_explosion: EventReader<SpawnExplosion>,
+) {
+    for explosion in spawn_explosion.read() {
+        commands.spawn((
+            Sprite {
+                image: game_textures.explosion_texture.clone(),
+                texture_atlas: Some(TextureAtlas {
+                    layout: game_textures.explosion_layout.clone(),
+                    index: 0,
+                }),
+                ..Default::default()
+            },
+            Transform::from_translation(explosion.position)
+                .with_scale(Vec3::splat(explosion.scale)),
+            Explosion,
+            ExplosionTimer::new(explosion.frame_duration),
+        ));
+    }
+}
+
 fn explosion_animation(
     mut commands: Commands,
     time: Res<Time>,
@@ -439,13 +1517,406 @@ fn explosion_animation(
 ) {
     for (entity, mut timer, mut sprite) in &mut query {
         timer.0.tick(time.delta());
-        if timer.0.finished() {
-            if let Some(texture) = sprite.texture_atlas.as_mut() {
-                texture.index += 1;
-                if texture.index >= EXPLOSION_LEN {
-                    commands.entity(entity).despawn();
-                }
+        let frames = timer.0.times_finished_this_tick() as usize;
+        if frames > 0
+            && let Some(texture) = sprite.texture_atlas.as_mut()
+        {
+            texture.index += frames;
+            if texture.index >= EXPLOSION_LEN {
+                commands.entity(entity).despawn();
             }
         }
     }
 }
+
+/// Throws debris out of every new explosion, on top of the sprite-sheet animation.
+fn explosion_particles(
+    mut commands: Commands,
+    reduce_effects: Res<ReduceEffects>,
+    query: Query<&Transform, Added<Explosion>>,
+) {
+    if **reduce_effects {
+        return;
+    }
+    let mut rng = rand::rng();
+    for transform in &query {
+        for _ in 0..EXPLOSION_PARTICLES {
+            let direction = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+            let velocity = direction * rng.random_range(0.2..1.0) * PARTICLE_SPEED;
+            commands.spawn((
+                Sprite::from_color(Color::srgb(1.0, 0.6, 0.2), Vec2::splat(4.0)),
+                Transform::from_translation(transform.translation.truncate().extend(15.0)),
+                Velocity {
+                    x: velocity.x,
+                    y: velocity.y,
+                },
+                Movable { auto_despawn: true },
+                Particle(Timer::from_seconds(PARTICLE_SECONDS, TimerMode::Once)),
+            ));
+        }
+    }
+}
+
+/// Drops a fading dot behind every laser each frame. The dots are bare sprites, so
+/// nothing collides with them.
+fn laser_trails(
+    mut commands: Commands,
+    reduce_effects: Res<ReduceEffects>,
+    query: Query<(&Transform, Has<FromPlayer>), With<Laser>>,
+) {
+    if **reduce_effects {
+        return;
+    }
+    for (transform, from_player) in &query {
+        let color = if from_player {
+            PLAYER_TRAIL_COLOR
+        } else {
+            ENEMY_TRAIL_COLOR
+        };
+        commands.spawn((
+            Sprite::from_color(color, Vec2::splat(3.0)),
+            Transform::from_translation(transform.translation.truncate().extend(0.5)),
+            Particle(Timer::from_seconds(TRAIL_SECONDS, TimerMode::Once)),
+        ));
+    }
+}
+
+fn score_popup_animation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in &mut query {
+        if popup.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += SCORE_POPUP_RISE * time.delta_secs();
+        color.0.set_alpha(popup.timer.fraction_remaining());
+    }
+}
+
+fn particle_fade(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Particle, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut sprite) in &mut query {
+        if particle.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite.color.set_alpha(particle.0.fraction_remaining());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::system::RunSystemOnce, state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::{bomb::bomb_detonate, enemy::enemy_cross_defense_line};
+
+    fn spawn_enemy_at(app: &mut App, position: Vec2) -> Entity {
+        app.world_mut()
+            .spawn((
+                Enemy,
+                Transform::from_translation(position.extend(10.0)),
+                SpriteSize(Vec2::splat(50.0)),
+            ))
+            .id()
+    }
+
+    fn enemy_count(app: &mut App) -> (u32, u32) {
+        let world = app.world_mut();
+        let live = world.query_filtered::<(), With<Enemy>>().iter(world).len() as u32;
+        (**world.resource::<EnemyCount>(), live)
+    }
+
+    #[test]
+    fn enemy_count_follows_despawns_from_every_path() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>()
+            .add_event::<EnemyKilled>()
+            .add_event::<SpawnExplosion>()
+            .insert_resource(Score(0))
+            .insert_resource(EnemyCount(3))
+            .insert_resource(WinSize { w: 800.0, h: 800.0 })
+            .insert_resource(Sandbox(false))
+            .insert_resource(Bombs(1))
+            .insert_resource(PlayerActions {
+                bomb: true,
+                ..default()
+            })
+            .init_resource::<ComboState>()
+            .init_resource::<Mutators>()
+            .init_resource::<ScreenShake>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        player_laser_hit_enemy,
+                        bomb_detonate,
+                        enemy_cross_defense_line,
+                    ),
+                    count_enemies,
+                )
+                    .chain(),
+            );
+
+        // the laser and the bomb both take out the first enemy in the same frame, and the
+        // one past the defense line ends the run while the bomb clears it
+        let shot = spawn_enemy_at(&mut app, Vec2::new(0.0, 200.0));
+        spawn_enemy_at(
+            &mut app,
+            Vec2::new(100.0, -400.0 + DEFENSE_LINE_OFFSET - 20.0),
+        );
+        spawn_enemy_at(&mut app, Vec2::new(-200.0, 100.0));
+        let shot_at = app.world().get::<Transform>(shot).unwrap().translation;
+        app.world_mut().spawn((
+            Laser,
+            FromPlayer,
+            Transform::from_translation(shot_at),
+            SpriteSize(Vec2::new(9.0, 54.0)),
+        ));
+
+        app.update();
+        assert_eq!(enemy_count(&mut app), (0, 0));
+
+        app.update();
+        assert_eq!(enemy_count(&mut app), (0, 0));
+        assert_eq!(
+            *app.world().resource::<State<GameState>>().get(),
+            GameState::GameOver
+        );
+    }
+
+    fn movement_app(despawn_margin: Option<f32>) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(WinSize { w: 800.0, h: 800.0 })
+            .insert_resource(GameConfig::default())
+            .insert_resource(Difficulty::default())
+            .insert_resource(DespawnMargin(despawn_margin))
+            .add_systems(Update, movement);
+        app
+    }
+
+    #[test]
+    fn zero_despawn_margin_despawns_just_past_the_edge() {
+        let mut app = movement_app(Some(0.0));
+        let movable = |x| {
+            (
+                Transform::from_xyz(x, 0.0, 10.0),
+                Velocity { x: 0.0, y: 0.0 },
+                Movable { auto_despawn: true },
+            )
+        };
+        let outside = app.world_mut().spawn(movable(401.0)).id();
+        let inside = app.world_mut().spawn(movable(399.0)).id();
+
+        app.update();
+        assert!(app.world().get_entity(outside).is_err());
+        assert!(app.world().get_entity(inside).is_ok());
+    }
+
+    #[test]
+    fn a_long_frame_still_registers_the_hit() {
+        let mut app = movement_app(Some(200.0));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            0.5,
+        )))
+        .add_event::<EnemyKilled>()
+        .add_event::<SpawnExplosion>()
+        .insert_resource(Score(0))
+        .init_resource::<ComboState>()
+        .init_resource::<Mutators>()
+        .add_systems(Update, player_laser_hit_enemy.after(movement));
+
+        let config = GameConfig::default();
+        let scale = Vec3::new(config.sprite_scale, config.sprite_scale, 1.0);
+        let enemy = app
+            .world_mut()
+            .spawn((
+                Enemy,
+                Transform::from_xyz(0.0, 0.0, 10.0).with_scale(scale),
+                SpriteSize(config.enemy_size),
+            ))
+            .id();
+        // just clear of the enemy: one capped step closes the gap, while an uncapped
+        // half-second step would carry the laser right past it
+        let gap = 10.0;
+        let laser_y =
+            -(config.enemy_size.y + config.player_laser_size.y) * config.sprite_scale / 2.0 - gap;
+        let laser = app
+            .world_mut()
+            .spawn((
+                Laser,
+                FromPlayer,
+                Transform::from_xyz(0.0, laser_y, 10.0).with_scale(scale),
+                SpriteSize(config.player_laser_size),
+                Velocity { x: 0.0, y: 1.0 },
+                Movable { auto_despawn: true },
+            ))
+            .id();
+
+        for _ in 0..2 {
+            app.update();
+        }
+        assert!(app.world().get_entity(enemy).is_err());
+        assert!(app.world().get_entity(laser).is_err());
+    }
+
+    /// The run resources as `main` sets them up for the first run.
+    fn fresh_run_app() -> App {
+        let config = GameConfig::default();
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(ClearColor(BACKGROUND_COLORS[0]))
+            .insert_resource(Score(0))
+            .insert_resource(HighScore(0))
+            .insert_resource(TargetScore(None))
+            .insert_resource(Lives(config.player_lives))
+            .insert_resource(MaxEnemies(config.max_enemies))
+            .insert_resource(LaserUpgrage(false))
+            .insert_resource(SpreadShot(false))
+            .insert_resource(PiercingShot(false))
+            .insert_resource(LaserIntercept(false))
+            .insert_resource(ContinuedRun(false))
+            .insert_resource(Difficulty::default())
+            .init_resource::<Mutators>()
+            .init_resource::<RunStats>()
+            .init_resource::<Credits>()
+            .insert_resource(config);
+        app
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct RunSnapshot {
+        score: u32,
+        lives: u32,
+        max_enemies: u32,
+        upgrades: [bool; 4],
+        target_score: Option<u32>,
+        stats: (u32, u32, bool),
+        credits: (u32, u32),
+        continued: bool,
+        clear_color: Color,
+        leftovers: usize,
+    }
+
+    fn run_snapshot(app: &mut App) -> RunSnapshot {
+        let world = app.world_mut();
+        let leftovers = world
+            .query_filtered::<(), Or<(With<Enemy>, With<Laser>)>>()
+            .iter(world)
+            .len();
+        let stats = world.resource::<RunStats>();
+        let credits = world.resource::<Credits>();
+        RunSnapshot {
+            score: **world.resource::<Score>(),
+            lives: **world.resource::<Lives>(),
+            max_enemies: **world.resource::<MaxEnemies>(),
+            upgrades: [
+                **world.resource::<LaserUpgrage>(),
+                **world.resource::<SpreadShot>(),
+                **world.resource::<PiercingShot>(),
+                **world.resource::<LaserIntercept>(),
+            ],
+            target_score: **world.resource::<TargetScore>(),
+            stats: (
+                stats.enemies_destroyed,
+                stats.longest_combo,
+                stats.new_high_score,
+            ),
+            credits: (credits.available, credits.earned),
+            continued: **world.resource::<ContinuedRun>(),
+            clear_color: world.resource::<ClearColor>().0,
+            leftovers,
+        }
+    }
+
+    #[test]
+    fn restart_matches_a_fresh_game() {
+        let mut app = fresh_run_app();
+        {
+            let world = app.world_mut();
+            **world.resource_mut::<Score>() = 420;
+            **world.resource_mut::<Lives>() = 1;
+            **world.resource_mut::<MaxEnemies>() = 9;
+            **world.resource_mut::<LaserUpgrage>() = true;
+            **world.resource_mut::<SpreadShot>() = true;
+            **world.resource_mut::<PiercingShot>() = true;
+            **world.resource_mut::<LaserIntercept>() = true;
+            **world.resource_mut::<ContinuedRun>() = true;
+            *world.resource_mut::<RunStats>() = RunStats {
+                enemies_destroyed: 40,
+                longest_combo: 12,
+                new_high_score: true,
+            };
+            world.resource_mut::<Credits>().available = 2;
+            world.resource_mut::<ClearColor>().0 = BACKGROUND_COLORS[3];
+            world.spawn(Enemy);
+            world.spawn(Enemy);
+            world.spawn((Laser, FromEnemy));
+        }
+        let player = app
+            .world_mut()
+            .spawn((
+                Player,
+                Transform::from_xyz(250.0, -350.0, 10.0),
+                Hitbox(0.1),
+                Visibility::Hidden,
+                Invulnerable::default(),
+            ))
+            .id();
+
+        app.world_mut()
+            .run_system_once(|mut run: RunReset| run.restart_run())
+            .unwrap();
+
+        assert_eq!(run_snapshot(&mut app), run_snapshot(&mut fresh_run_app()));
+        let world = app.world();
+        assert_eq!(world.get::<Transform>(player).unwrap().translation.x, 0.0);
+        assert_eq!(
+            world.get::<Hitbox>(player).unwrap().0,
+            PLAYER_HITBOX_SCALE * Difficulty::default().player_hitbox_scale()
+        );
+        assert_eq!(
+            *world.get::<Visibility>(player).unwrap(),
+            Visibility::Inherited
+        );
+        assert!(world.get::<Invulnerable>(player).is_none());
+    }
+
+    #[test]
+    fn a_continue_keeps_the_run_seed() {
+        let config = GameConfig::default();
+        let mut app = App::new();
+        app.insert_resource(GameRng::new(&config))
+            .insert_resource(ContinuedRun(false));
+        app.world_mut().run_system_once(rng_reseed).unwrap();
+        let seed = app.world().resource::<GameRng>().seed;
+
+        **app.world_mut().resource_mut::<ContinuedRun>() = true;
+        app.world_mut().run_system_once(rng_reseed).unwrap();
+        assert_eq!(app.world().resource::<GameRng>().seed, seed);
+    }
+
+    #[test]
+    fn title_screen_lists_the_remapped_keys() {
+        let bindings = KeyBindings {
+            move_left: KeyCode::KeyJ,
+            move_right: KeyCode::KeyL,
+            fire: KeyCode::KeyI,
+            ..default()
+        };
+        let help = controls_help(&bindings);
+        assert!(
+            help.starts_with("move: [j] & [l]\nshoot: [i]\nmissile: [down-arrow]\npause: [esc]")
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::{ecs::spawn::SpawnIter, prelude::*, tasks::block_on};
+
+use crate::{
+    GameState, HighScore, PendingHighScoreWrite, RunReset,
+    components::{LeaderboardPanel, MainMenu, MenuButton},
+    difficulty::Difficulty,
+    input::PlayerActions,
+    stats::Stats,
+    stats_summary,
+};
+
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.08);
+const BUTTON_HOVERED_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.18);
+const BUTTON_SELECTED_COLOR: Color = Color::srgba(0.3, 0.6, 1.0, 0.45);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Start,
+    Difficulty,
+    Leaderboard,
+    Settings,
+    Quit,
+}
+
+impl MenuAction {
+    const ALL: [MenuAction; 5] = [
+        MenuAction::Start,
+        MenuAction::Difficulty,
+        MenuAction::Leaderboard,
+        MenuAction::Settings,
+        MenuAction::Quit,
+    ];
+
+    fn label(&self, difficulty: Difficulty) -> String {
+        match self {
+            MenuAction::Start => "Start".to_string(),
+            MenuAction::Difficulty => format!("Difficulty: {}", difficulty.name()),
+            MenuAction::Leaderboard => "Leaderboard".to_string(),
+            MenuAction::Settings => "Settings".to_string(),
+            MenuAction::Quit => "Quit".to_string(),
+        }
+    }
+}
+
+/// Index into `MenuAction::ALL` of the highlighted main menu button.
+#[derive(Resource, Deref, DerefMut, Default)]
+struct MenuSelection(usize);
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuSelection>()
+            .add_systems(OnEnter(GameState::MainMenu), menu_selection_reset)
+            .add_systems(
+                Update,
+                (menu_navigate, menu_activate, menu_highlight)
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+/// Column of main menu buttons, one per `MenuAction`.
+pub fn menu_buttons() -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            margin: UiRect::top(Val::Px(30.0)),
+            ..default()
+        },
+        Children::spawn(SpawnIter(MenuAction::ALL.into_iter().map(|action| {
+            (
+                Button,
+                Node {
+                    width: Val::Px(240.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(BUTTON_COLOR),
+                MenuButton(action),
+                children![Text::default()],
+            )
+        }))),
+    )
+}
+
+fn menu_selection_reset(mut selection: ResMut<MenuSelection>) {
+    **selection = 0;
+}
+
+/// Moves the selection with the arrow keys or d-pad, or to whichever button the mouse
+/// is over.
+fn menu_navigate(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut selection: ResMut<MenuSelection>,
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+) {
+    let up = keyboard.just_pressed(KeyCode::ArrowUp)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::DPadUp));
+    let down = keyboard.just_pressed(KeyCode::ArrowDown)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::DPadDown));
+
+    let count = MenuAction::ALL.len();
+    if up {
+        **selection = (**selection + count - 1) % count;
+    }
+    if down {
+        **selection = (**selection + 1) % count;
+    }
+
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::None
+            && let Some(index) = MenuAction::ALL
+                .iter()
+                .position(|action| *action == button.0)
+        {
+            **selection = index;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn menu_activate(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    selection: Res<MenuSelection>,
+    button_query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    main_menu_query: Query<Entity, With<MainMenu>>,
+    leaderboard_query: Query<Entity, With<LeaderboardPanel>>,
+    mut difficulty: ResMut<Difficulty>,
+    high_score: Res<HighScore>,
+    stats: Res<Stats>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit: EventWriter<AppExit>,
+    mut pending_write: ResMut<PendingHighScoreWrite>,
+    mut run: RunReset,
+) {
+    let clicked = button_query
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, button)| button.0);
+    let action = if actions.confirm {
+        MenuAction::ALL[**selection]
+    } else if let Some(action) = clicked {
+        action
+    } else if keyboard.just_pressed(KeyCode::KeyQ) {
+        MenuAction::Quit
+    } else {
+        return;
+    };
+
+    match action {
+        MenuAction::Start => {
+            for entity in &main_menu_query {
+                commands.entity(entity).despawn();
+            }
+            run.restart_run();
+            next_state.set(GameState::Playing);
+        }
+        MenuAction::Difficulty => {
+            *difficulty = difficulty.cycled();
+        }
+        MenuAction::Leaderboard => {
+            if let Ok(entity) = leaderboard_query.single() {
+                commands.entity(entity).despawn();
+                return;
+            }
+            commands.spawn((
+                Text::new(format!(
+                    "Leaderboard\n\nHigh Score: {}\n{}",
+                    **high_score,
+                    stats_summary(&stats)
+                )),
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(100.0),
+                    right: Val::Px(60.0),
+                    ..default()
+                },
+                LeaderboardPanel,
+                MainMenu,
+            ));
+        }
+        MenuAction::Settings => {
+            // the title screen is spawned again on the way back
+            for entity in &main_menu_query {
+                commands.entity(entity).despawn();
+            }
+            next_state.set(GameState::Settings);
+        }
+        MenuAction::Quit => {
+            if let Some(write) = pending_write.take() {
+                block_on(write);
+            }
+            app_exit.write(AppExit::Success);
+        }
+    }
+}
+
+fn menu_highlight(
+    selection: Res<MenuSelection>,
+    difficulty: Res<Difficulty>,
+    mut button_query: Query<(&Interaction, &MenuButton, &mut BackgroundColor, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, button, mut background, children) in &mut button_query {
+        let selected = MenuAction::ALL[**selection] == button.0;
+        let color = if selected {
+            BUTTON_SELECTED_COLOR
+        } else if *interaction == Interaction::Hovered {
+            BUTTON_HOVERED_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+        background.set_if_neq(BackgroundColor(color));
+
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.set_if_neq(Text(button.0.label(*difficulty)));
+            }
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    GameState, GameTextures, Sandbox, Score,
+    components::{Enemy, Homing, Player, Velocity},
+    config::GameConfig,
+    input::PlayerActions,
+    pause::Pause,
+    player::spawn_player_laser,
+    settings::Palette,
+    targeting::nearest_enemy,
+};
+
+/// Radians per second a missile can turn, low enough that it arcs onto its target.
+const MISSILE_TURN_RATE: f32 = 3.0;
+const MISSILE_SPEED: f32 = 0.9;
+
+/// Unlocked once the run reaches `homing_missile_score`.
+#[derive(Resource, Deref, DerefMut)]
+pub struct HomingMissiles(bool);
+
+#[derive(Resource, Deref, DerefMut)]
+struct MissileCooldown(Timer);
+
+impl FromWorld for MissileCooldown {
+    fn from_world(world: &mut World) -> Self {
+        let seconds = world.resource::<GameConfig>().missile_cooldown;
+        let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+        // start ready to fire
+        timer.tick(Duration::from_secs_f32(seconds));
+        Self(timer)
+    }
+}
+
+pub struct MissilePlugin;
+impl Plugin for MissilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HomingMissiles(false))
+            .init_resource::<MissileCooldown>()
+            .add_systems(OnEnter(GameState::Playing), missile_reset)
+            .add_systems(
+                Update,
+                (missile_unlock, missile_fire)
+                    .chain()
+                    .run_if(in_state(Pause::Running)),
+            )
+            .add_systems(Update, homing_steer);
+    }
+}
+
+fn missile_reset(mut homing_missiles: ResMut<HomingMissiles>) {
+    **homing_missiles = false;
+}
+
+fn missile_unlock(
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    mut homing_missiles: ResMut<HomingMissiles>,
+) {
+    if **score >= config.homing_missile_score && !**homing_missiles {
+        **homing_missiles = true;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn missile_fire(
+    mut commands: Commands,
+    time: Res<Time>,
+    actions: Res<PlayerActions>,
+    homing_missiles: Res<HomingMissiles>,
+    sandbox: Res<Sandbox>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    game_textures: Res<GameTextures>,
+    mut cooldown: ResMut<MissileCooldown>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    cooldown.tick(time.delta());
+    if !actions.missile || !(**homing_missiles || **sandbox) {
+        return;
+    }
+    if !cooldown.finished() && !**sandbox {
+        return;
+    }
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    cooldown.reset();
+
+    spawn_player_laser(
+        &mut commands,
+        &config,
+        *palette,
+        game_textures.player_laser_upgrade.clone(),
+        player_tf.translation.truncate() + Vec2::new(0.0, 20.0),
+        Vec2::new(0.0, MISSILE_SPEED),
+    )
+    .insert(Homing {
+        turn_rate: MISSILE_TURN_RATE,
+    });
+}
+
+/// Turns homing projectiles toward the nearest enemy, keeping their speed. With no
+/// enemy around they fly straight and leave the screen like any other laser.
+#[allow(clippy::type_complexity)]
+fn homing_steer(
+    time: Res<Time>,
+    enemy_query: Query<(Entity, &Transform), (With<Enemy>, Without<Homing>)>,
+    mut homing_query: Query<(&Homing, &mut Velocity, &mut Transform)>,
+) {
+    for (homing, mut velocity, mut transform) in &mut homing_query {
+        let from = transform.translation.truncate();
+        let Some((_, target_tf)) = nearest_enemy(
+            from,
+            enemy_query
+                .iter()
+                .map(|(entity, tf)| (entity, tf.translation.truncate())),
+        )
+        .and_then(|entity| enemy_query.get(entity).ok()) else {
+            continue;
+        };
+
+        let current = Vec2::new(velocity.x, velocity.y);
+        let wanted = target_tf.translation.truncate() - from;
+        let max_turn = homing.turn_rate * time.delta_secs();
+        let turn = current.angle_to(wanted).clamp(-max_turn, max_turn);
+        let steered = Vec2::from_angle(turn).rotate(current);
+
+        velocity.x = steered.x;
+        velocity.y = steered.y;
+        transform.rotation = Quat::from_rotation_z(-steered.x.atan2(steered.y));
+    }
+}
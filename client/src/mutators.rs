@@ -0,0 +1,233 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState,
+    components::{FogOverlay, MainMenu, MutatorMenu, Player},
+    get_config_path,
+    save::{load_json, save_json},
+};
+
+/// Side of the generated fog texture, in pixels.
+const FOG_TEXTURE_SIZE: u32 = 256;
+/// Size the fog is drawn at, big enough to cover the window wherever the player is.
+const FOG_SIZE: f32 = 4000.0;
+/// Radius of the clear area around the player, in world units.
+const FOG_CLEAR_RADIUS: f32 = 180.0;
+
+/// Optional rule changes for a run.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mutator {
+    /// Darkness everywhere except a small circle around the player.
+    Fog,
+    /// A single life, but every kill is worth double.
+    GlassCannon,
+    /// Three times as many enemies, none of them armored or cloaked.
+    Swarm,
+}
+
+impl Mutator {
+    const ALL: [(Mutator, KeyCode); 3] = [
+        (Mutator::Fog, KeyCode::Digit1),
+        (Mutator::GlassCannon, KeyCode::Digit2),
+        (Mutator::Swarm, KeyCode::Digit3),
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Mutator::Fog => "fog",
+            Mutator::GlassCannon => "glass cannon",
+            Mutator::Swarm => "swarm",
+        }
+    }
+}
+
+/// Mutators picked on the main menu. The last used set is remembered across launches;
+/// with none enabled the base rules apply unchanged.
+#[derive(Resource, Deref, DerefMut, Default, Serialize, Deserialize)]
+pub struct Mutators(HashSet<Mutator>);
+
+impl Mutators {
+    /// Multiplier applied to points from kills.
+    pub fn score_multiplier(&self) -> u32 {
+        if self.contains(&Mutator::GlassCannon) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Lives at the start of a run, given the normal count.
+    pub fn starting_lives(&self, normal: u32) -> u32 {
+        if self.contains(&Mutator::GlassCannon) {
+            1
+        } else {
+            normal
+        }
+    }
+
+    /// Scales an enemy cap.
+    pub fn max_enemies(&self, normal: u32) -> u32 {
+        if self.contains(&Mutator::Swarm) {
+            normal * 3
+        } else {
+            normal
+        }
+    }
+
+    /// Whether randomly spawned enemies may be tougher variants.
+    pub fn special_enemies(&self) -> bool {
+        !self.contains(&Mutator::Swarm)
+    }
+}
+
+#[derive(Resource, Deref)]
+struct MutatorsPath(PathBuf);
+
+pub struct MutatorsPlugin;
+impl Plugin for MutatorsPlugin {
+    fn build(&self, app: &mut App) {
+        let mutators_path = get_config_path("mutators.json").unwrap_or_default();
+        let mutators: Mutators = load_json(&mutators_path);
+
+        app.insert_resource(mutators)
+            .insert_resource(MutatorsPath(mutators_path))
+            .add_systems(OnEnter(GameState::MainMenu), mutator_menu_spawn)
+            .add_systems(
+                Update,
+                (select_mutators, mutator_menu_update)
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            )
+            .add_systems(OnEnter(GameState::Playing), fog_spawn)
+            .add_systems(OnExit(GameState::Playing), fog_despawn)
+            .add_systems(Update, fog_follow.run_if(in_state(GameState::Playing)));
+    }
+}
+
+fn mutator_menu_spawn(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        TextLayout::new_with_justify(JustifyText::Right),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(20.0),
+            right: Val::Px(20.0),
+            ..default()
+        },
+        MutatorMenu,
+        MainMenu,
+    ));
+}
+
+fn select_mutators(
+    input: Res<ButtonInput<KeyCode>>,
+    mut mutators: ResMut<Mutators>,
+    mutators_path: Res<MutatorsPath>,
+) {
+    let mut changed = false;
+    for (mutator, key) in Mutator::ALL {
+        if input.just_pressed(key) {
+            if !mutators.remove(&mutator) {
+                mutators.insert(mutator);
+            }
+            changed = true;
+        }
+    }
+    if changed {
+        let _ = save_json(&mutators_path, &*mutators);
+    }
+}
+
+fn mutator_menu_update(mutators: Res<Mutators>, mut query: Query<&mut Text, With<MutatorMenu>>) {
+    let mut menu = String::from("mutators");
+    for (index, (mutator, _)) in Mutator::ALL.iter().enumerate() {
+        let state = if mutators.contains(mutator) {
+            "on"
+        } else {
+            "off"
+        };
+        menu.push_str(&format!("\n{} [{}]: {}", mutator.name(), index + 1, state));
+    }
+    for mut text in &mut query {
+        text.set_if_neq(Text(menu.clone()));
+    }
+}
+
+/// Black texture that fades to transparent towards its center.
+fn fog_image() -> Image {
+    let size = FOG_TEXTURE_SIZE;
+    let center = size as f32 / 2.0;
+    let clear_radius = FOG_CLEAR_RADIUS / FOG_SIZE * size as f32;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = Vec2::new(x as f32 + 0.5 - center, y as f32 + 0.5 - center).length();
+            // soft edge between the clear circle and full darkness
+            let alpha = ((distance - clear_radius * 0.6) / (clear_radius * 0.4)).clamp(0.0, 1.0);
+            data.extend_from_slice(&[0, 0, 0, (alpha * 255.0) as u8]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+fn fog_spawn(
+    mut commands: Commands,
+    mutators: Res<Mutators>,
+    mut images: ResMut<Assets<Image>>,
+    fog_query: Query<Entity, With<FogOverlay>>,
+) {
+    // restarting from the pause menu re-enters the state without leaving it
+    for entity in &fog_query {
+        commands.entity(entity).despawn();
+    }
+    if !mutators.contains(&Mutator::Fog) {
+        return;
+    }
+
+    commands.spawn((
+        Sprite {
+            image: images.add(fog_image()),
+            custom_size: Some(Vec2::splat(FOG_SIZE)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 50.0),
+        FogOverlay,
+    ));
+}
+
+fn fog_despawn(mut commands: Commands, fog_query: Query<Entity, With<FogOverlay>>) {
+    for entity in &fog_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn fog_follow(
+    player_query: Query<&Transform, (With<Player>, Without<FogOverlay>)>,
+    mut fog_query: Query<&mut Transform, With<FogOverlay>>,
+) {
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    for mut transform in &mut fog_query {
+        transform.translation.x = player_tf.translation.x;
+        transform.translation.y = player_tf.translation.y;
+    }
+}
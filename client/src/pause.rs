@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameState, RunReset, centered_menu,
+    components::{Enemy, Laser, PauseMenu},
+    input::{KeyBindings, PlayerActions, key_name},
+    main_menu,
+};
+
+/// Sub-state of `Playing`; virtual time is stopped while paused.
+#[derive(SubStates, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum Pause {
+    #[default]
+    Running,
+    Paused,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PauseAction {
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseAction {
+    const ALL: [PauseAction; 3] = [PauseAction::Resume, PauseAction::Restart, PauseAction::Quit];
+
+    fn label(&self, bindings: &KeyBindings) -> String {
+        match self {
+            PauseAction::Resume => "resume".to_string(),
+            PauseAction::Restart => format!("restart [{}]", key_name(bindings.restart)),
+            PauseAction::Quit => "quit to menu".to_string(),
+        }
+    }
+}
+
+/// Index into `PauseAction::ALL` of the highlighted pause menu row.
+#[derive(Resource, Deref, DerefMut, Default)]
+struct PauseSelection(usize);
+
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<Pause>()
+            .init_resource::<PauseSelection>()
+            .add_systems(Update, pause_toggle.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                Update,
+                (pause_navigate, pause_activate, pause_menu_update)
+                    .chain()
+                    .run_if(in_state(Pause::Paused)),
+            )
+            .add_systems(OnEnter(Pause::Paused), pause_enter)
+            .add_systems(OnExit(Pause::Paused), pause_exit);
+    }
+}
+
+pub fn pause_toggle(
+    actions: Res<PlayerActions>,
+    pause: Res<State<Pause>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    if actions.pause {
+        next_pause.set(match pause.get() {
+            Pause::Running => Pause::Paused,
+            Pause::Paused => Pause::Running,
+        });
+    }
+}
+
+fn pause_enter(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut selection: ResMut<PauseSelection>,
+) {
+    // enemy fire and spawn timers run on virtual time, so nothing builds up while
+    // paused to go off all at once on resuming
+    time.pause();
+    **selection = 0;
+    commands.spawn((centered_menu(String::new()), PauseMenu));
+}
+
+fn pause_exit(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    menu_query: Query<Entity, With<PauseMenu>>,
+) {
+    time.unpause();
+    for entity in &menu_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Up and down pick a row, from the arrow keys or d-pad.
+fn pause_navigate(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut selection: ResMut<PauseSelection>,
+) {
+    let up = keyboard.just_pressed(KeyCode::ArrowUp)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::DPadUp));
+    let down = keyboard.just_pressed(KeyCode::ArrowDown)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::DPadDown));
+
+    let count = PauseAction::ALL.len();
+    if up {
+        **selection = (**selection + count - 1) % count;
+    }
+    if down {
+        **selection = (**selection + 1) % count;
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn pause_activate(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    bindings: Res<KeyBindings>,
+    selection: Res<PauseSelection>,
+    mut run: RunReset,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    leftover_query: Query<Entity, Or<(With<Enemy>, With<Laser>)>>,
+) {
+    let action = if actions.restart {
+        PauseAction::Restart
+    } else if actions.confirm {
+        PauseAction::ALL[**selection]
+    } else {
+        return;
+    };
+
+    match action {
+        PauseAction::Resume => next_pause.set(Pause::Running),
+        PauseAction::Restart => {
+            // clears the enemies and lasers along with the score
+            run.restart_run();
+            // replay the per-run resets every plugin hooks on entering `Playing`,
+            // without leaving the state
+            commands.run_schedule(OnEnter(GameState::Playing));
+            next_pause.set(Pause::Running);
+        }
+        PauseAction::Quit => {
+            for entity in &leftover_query {
+                commands.entity(entity).despawn();
+            }
+            commands.spawn(main_menu(&bindings));
+            next_state.set(GameState::MainMenu);
+        }
+    }
+}
+
+fn pause_menu_update(
+    selection: Res<PauseSelection>,
+    bindings: Res<KeyBindings>,
+    menu_query: Query<&Children, With<PauseMenu>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let rows: Vec<String> = PauseAction::ALL
+        .iter()
+        .enumerate()
+        .map(|(row, action)| {
+            let marker = if row == **selection { ">" } else { " " };
+            format!("{marker} {}", action.label(&bindings))
+        })
+        .collect();
+    let text = format!(
+        "Paused\n\n{}\n\nresume [{}]",
+        rows.join("\n"),
+        key_name(bindings.pause)
+    );
+    for children in &menu_query {
+        for child in children {
+            if let Ok(mut child_text) = text_query.get_mut(*child) {
+                child_text.set_if_neq(Text(text.clone()));
+            }
+        }
+    }
+}
@@ -1,81 +1,357 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 use crate::{
-    GameState, GameTextures, LaserUpgrage, PLAYER_LASER_SIZE, PLAYER_MAX_LASERS, PLAYER_SIZE,
-    SPRITE_SCALE, WinSize,
-    components::{FromPlayer, Laser, Movable, Player, SpriteSize, Velocity},
+    GameState, GameTextures, LaserUpgrage, Momentum, PLAYER_HITBOX_SCALE, PiercingShot, Sandbox,
+    SpreadShot, WinSize, WrapMode,
+    components::{
+        FromPlayer, HeatUI, Hitbox, Invulnerable, Laser, Lifetime, Movable, Piercing, Player,
+        SpriteSize, Velocity,
+    },
+    config::GameConfig,
+    difficulty::Difficulty,
+    events::PlayerFired,
+    input::PlayerActions,
+    movement,
+    pause::Pause,
+    settings::Palette,
 };
 
+/// Distance from the cursor below which the ship eases off instead of moving at full
+/// speed, so it settles under the cursor without overshooting.
+const MOUSE_FOLLOW_DISTANCE: f32 = 30.0;
+/// Fire has to be held this long before letting go releases a beam, so taps stay
+/// ordinary shots.
+const CHARGE_MIN_SECONDS: f32 = 0.4;
+/// Holding this long charges the beam fully.
+const CHARGE_FULL_SECONDS: f32 = 1.5;
+/// Width and length multipliers of a fully charged beam over a normal laser.
+const BEAM_MAX_SCALE: Vec2 = Vec2::new(5.0, 2.5);
+const BEAM_VELOCITY: f32 = 2.0;
+const HEAT_METER_LEN: usize = 10;
+const OVERHEAT_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+/// Seconds between flashes of the heat gauge while the gun is overheated.
+const OVERHEAT_BLINK: f32 = 0.2;
+
+/// How long fire has been held toward a charged beam.
+#[derive(Resource, Default)]
+pub struct ChargeState {
+    held: f32,
+}
+
+impl ChargeState {
+    /// Charge from 0.0 to 1.0, or `None` while too short to release a beam.
+    pub fn level(&self) -> Option<f32> {
+        (self.held >= CHARGE_MIN_SECONDS).then(|| (self.held / CHARGE_FULL_SECONDS).min(1.0))
+    }
+}
+
+/// Gun temperature from 0.0 to 1.0. Every shot heats it up; reaching 1.0 overheats
+/// the gun, which can't fire again until it has cooled all the way down.
+#[derive(Resource, Default)]
+pub struct Heat {
+    pub value: f32,
+    pub overheated: bool,
+}
+
+/// Minimum delay between two player shots.
+#[derive(Resource, Deref, DerefMut)]
+pub struct FireCooldown(Timer);
+
+impl FromWorld for FireCooldown {
+    fn from_world(world: &mut World) -> Self {
+        let seconds = world.resource::<GameConfig>().player_fire_cooldown;
+        let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+        // start ready to fire
+        timer.tick(Duration::from_secs_f32(seconds));
+        Self(timer)
+    }
+}
+
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::MainMenu), player_spawn)
-            .add_systems(Update, player_input)
-            .add_systems(Update, player_fire);
+        app.init_resource::<FireCooldown>()
+            .init_resource::<ChargeState>()
+            .init_resource::<Heat>()
+            .add_systems(Startup, heat_ui_spawn)
+            .add_systems(OnEnter(GameState::MainMenu), player_spawn)
+            // back after a continue
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (player_spawn, charge_reset, heat_reset),
+            )
+            .add_systems(OnExit(GameState::Playing), heat_ui_clear)
+            // only while a run is live, so nothing steers or fires once the player is
+            // dead and the game-over explosion plays out
+            .add_systems(Update, player_input.run_if(in_state(Pause::Running)))
+            .add_systems(Update, player_bounds.after(movement))
+            .add_systems(Update, player_invulnerability)
+            .add_systems(
+                Update,
+                (fire_cooldown_tick, heat_cool, player_fire, update_heat_ui)
+                    .chain()
+                    .run_if(in_state(Pause::Running)),
+            );
     }
 }
 
-fn player_spawn(mut commands: Commands, game_textures: Res<GameTextures>, win_size: Res<WinSize>) {
+/// Puts a ship on the field unless one is already there.
+fn player_spawn(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    win_size: Res<WinSize>,
+    config: Res<GameConfig>,
+    difficulty: Res<Difficulty>,
+    player_query: Query<(), With<Player>>,
+) {
+    if !player_query.is_empty() {
+        return;
+    }
     let bottom = -win_size.h / 2.0;
+    let scale = config.sprite_scale;
     commands
         .spawn((
             Sprite::from_image(game_textures.player.clone()),
             Transform {
-                translation: Vec3::new(0., bottom + PLAYER_SIZE.1 / 2. * SPRITE_SCALE + 5., 10.),
-                scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
+                translation: Vec3::new(0., bottom + config.player_size.y / 2. * scale + 5., 10.),
+                scale: Vec3::new(scale, scale, 1.),
                 ..Default::default()
             },
         ))
         .insert(Player)
-        .insert(SpriteSize::from(PLAYER_SIZE))
+        .insert(SpriteSize(config.player_size))
+        .insert(Hitbox(
+            PLAYER_HITBOX_SCALE * difficulty.player_hitbox_scale(),
+        ))
         .insert(Movable {
             auto_despawn: false,
         })
         .insert(Velocity { x: 0.0, y: 0.0 });
 }
 
+/// Furthest the ship's center may get from the middle of the screen with its wings
+/// still on it.
+pub fn player_edge(win_size: &WinSize, config: &GameConfig) -> f32 {
+    win_size.w / 2. - config.player_size.x / 2. * config.sprite_scale
+}
+
 fn player_input(
-    input: Res<ButtonInput<KeyCode>>,
-    win_size: Res<WinSize>,
+    time: Res<Time>,
+    actions: Res<PlayerActions>,
+    config: Res<GameConfig>,
+    momentum: Res<Momentum>,
     mut query: Query<(&mut Velocity, &Transform), With<Player>>,
 ) {
     if let Ok((mut velocity, transform)) = query.single_mut() {
-        let x = if input.pressed(KeyCode::KeyA) {
-            -1.0
-        } else if input.pressed(KeyCode::KeyD) {
-            1.0
-        } else {
-            0.0
+        let translation = transform.translation;
+        // keys and sticks win over the mouse while they are held
+        let x = match actions.aim_x {
+            Some(aim_x) if actions.move_x == 0.0 => {
+                ((aim_x - translation.x) / MOUSE_FOLLOW_DISTANCE).clamp(-1.0, 1.0)
+            }
+            _ => actions.move_x,
         };
 
+        if **momentum {
+            // speed up toward the held direction, or coast down to a stop
+            let rate = if x != 0.0 {
+                config.player_acceleration
+            } else {
+                config.player_friction
+            };
+            let step = rate * time.delta_secs();
+            velocity.x += (x - velocity.x).clamp(-step, step);
+        } else {
+            velocity.x = x;
+        }
+    }
+}
+
+/// Keeps the ship on screen once `movement` has moved it this frame: wrapped around in
+/// wrap mode, otherwise held at the edge, bouncing off it with momentum on.
+fn player_bounds(
+    win_size: Res<WinSize>,
+    config: Res<GameConfig>,
+    wrap_mode: Res<WrapMode>,
+    momentum: Res<Momentum>,
+    mut query: Query<(&mut Velocity, &mut Transform), With<Player>>,
+) {
+    if let Ok((mut velocity, mut transform)) = query.single_mut() {
         let translation = transform.translation;
-        if translation.x < -win_size.w / 2. + PLAYER_SIZE.1 / 2. && x < 0.0 {
-            velocity.x = 0.0;
+        if **wrap_mode {
+            let half_w = win_size.w / 2.;
+            if translation.x > half_w {
+                transform.translation.x = -half_w;
+            } else if translation.x < -half_w {
+                transform.translation.x = half_w;
+            }
             return;
         }
-        if translation.x > win_size.w / 2. - PLAYER_SIZE.1 / 2. && x > 0.0 {
-            velocity.x = 0.0;
+
+        let edge = player_edge(&win_size, &config);
+        if translation.x.abs() <= edge {
             return;
         }
+        transform.translation.x = edge * translation.x.signum();
+        if velocity.x * translation.x > 0.0 {
+            velocity.x = if **momentum {
+                -velocity.x * config.wall_bounce
+            } else {
+                0.0
+            };
+        }
+    }
+}
+
+fn player_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Player>>,
+) {
+    for (entity, mut invulnerable, mut visibility) in &mut query {
+        invulnerable.timer.tick(time.delta());
+        if invulnerable.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+            continue;
+        }
+
+        // toggle once per elapsed blink interval, independent of frame rate
+        let toggles = invulnerable
+            .blink
+            .tick(time.delta())
+            .times_finished_this_tick();
+        if toggles % 2 == 1 {
+            visibility.toggle_inherited_hidden();
+        }
+    }
+}
+
+fn charge_reset(mut charge: ResMut<ChargeState>) {
+    *charge = ChargeState::default();
+}
 
-        velocity.x = x;
+fn heat_reset(mut heat: ResMut<Heat>) {
+    *heat = Heat::default();
+}
+
+fn heat_cool(time: Res<Time>, config: Res<GameConfig>, mut heat: ResMut<Heat>) {
+    if heat.value <= 0.0 {
+        return;
+    }
+    heat.value = (heat.value - config.heat_cooling_rate * time.delta_secs()).max(0.0);
+    if heat.value == 0.0 {
+        heat.overheated = false;
     }
 }
 
+fn heat_ui_spawn(mut commands: Commands) {
+    commands.spawn((
+        Text::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(55.0),
+            left: Val::Px(5.0),
+            ..default()
+        },
+        HeatUI,
+    ));
+}
+
+/// Shows the heat as a gauge, flashing red while overheated.
+fn update_heat_ui(
+    time: Res<Time>,
+    heat: Res<Heat>,
+    mut query: Query<(&mut Text, &mut TextColor), With<HeatUI>>,
+) {
+    let filled = (heat.value * HEAT_METER_LEN as f32).round() as usize;
+    let gauge = format!(
+        "Heat: [{}{}]{}",
+        "#".repeat(filled),
+        "-".repeat(HEAT_METER_LEN - filled),
+        if heat.overheated { " OVERHEAT" } else { "" }
+    );
+    let flash =
+        heat.overheated && ((time.elapsed_secs() / OVERHEAT_BLINK) as u32).is_multiple_of(2);
+    for (mut text, mut color) in &mut query {
+        text.set_if_neq(Text(gauge.clone()));
+        color.set_if_neq(TextColor(if flash { OVERHEAT_COLOR } else { Color::WHITE }));
+    }
+}
+
+fn heat_ui_clear(mut query: Query<&mut Text, With<HeatUI>>) {
+    for mut text in &mut query {
+        text.0.clear();
+    }
+}
+
+fn fire_cooldown_tick(time: Res<Time>, mut cooldown: ResMut<FireCooldown>) {
+    cooldown.tick(time.delta());
+}
+
+#[allow(clippy::too_many_arguments)]
 fn player_fire(
     mut commands: Commands,
-    input: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    actions: Res<PlayerActions>,
+    mut charge: ResMut<ChargeState>,
     game_textures: Res<GameTextures>,
     laser_velocity_upgrade: Res<LaserUpgrage>,
+    spread_shot: Res<SpreadShot>,
+    piercing_shot: Res<PiercingShot>,
+    palette: Res<Palette>,
+    sandbox: Res<Sandbox>,
+    mut cooldown: ResMut<FireCooldown>,
+    config: Res<GameConfig>,
     query: Query<&Transform, With<Player>>,
     player_laser_query: Query<(), (With<Laser>, With<FromPlayer>)>,
+    mut player_fired: EventWriter<PlayerFired>,
+    mut heat: ResMut<Heat>,
 ) {
     if let Ok(player_tf) = query.single() {
-        if input.just_pressed(KeyCode::ArrowUp)
-            && player_laser_query.iter().len() < PLAYER_MAX_LASERS
+        // holding charges a beam on top of the shot fired on the press
+        if actions.fire_held {
+            charge.held += time.delta_secs();
+        } else {
+            if let Some(level) = charge.level() {
+                let position = player_tf.translation.truncate() + Vec2::new(0., 15.);
+                let scale = Vec2::ONE.lerp(BEAM_MAX_SCALE, level) * config.sprite_scale;
+                spawn_player_laser(
+                    &mut commands,
+                    &config,
+                    *palette,
+                    game_textures.player_laser_upgrade.clone(),
+                    position,
+                    Vec2::new(0., BEAM_VELOCITY),
+                )
+                // the hitbox grows with the sprite, through its scale
+                .insert(Transform {
+                    translation: position.extend(1.0),
+                    scale: scale.extend(1.0),
+                    ..Default::default()
+                })
+                .insert(Piercing::default());
+                player_fired.write(PlayerFired);
+            }
+            charge.held = 0.0;
+        }
+
+        if actions.fire
+            && (cooldown.finished() || **sandbox)
+            && (!heat.overheated || **sandbox)
+            && player_laser_query.iter().len() < config.player_max_lasers
         {
+            cooldown.reset();
+            player_fired.write(PlayerFired);
+            if !**sandbox {
+                heat.value = (heat.value + config.heat_per_shot).min(1.0);
+                heat.overheated |= heat.value >= 1.0;
+            }
+
             let (x, y) = (player_tf.translation.x, player_tf.translation.y);
-            let x_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.;
+            let x_offset = config.player_size.x / 2. * config.sprite_scale - 5.;
             let laser_velocity = if **laser_velocity_upgrade { 2.0 } else { 1.0 };
             let laser_sprite = if **laser_velocity_upgrade {
                 game_textures.player_laser_upgrade.clone()
@@ -83,29 +359,206 @@ fn player_fire(
                 game_textures.player_laser.clone()
             };
 
-            let mut spawn_lazer =
-                |x_offset: f32, laser_velocity: f32, laser_sprite: Handle<Image>| {
-                    commands
-                        .spawn((
-                            Sprite::from_image(laser_sprite),
-                            Transform {
-                                translation: Vec3::new(x + x_offset, y + 15., 1.0),
-                                scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
-                                ..Default::default()
-                            },
-                        ))
-                        .insert(Laser)
-                        .insert(FromPlayer)
-                        .insert(SpriteSize::from(PLAYER_LASER_SIZE))
-                        .insert(Movable { auto_despawn: true })
-                        .insert(Velocity {
-                            x: 0.0,
-                            y: laser_velocity,
-                        });
-                };
-
-            spawn_lazer(x_offset, laser_velocity, laser_sprite.clone());
-            spawn_lazer(-x_offset, laser_velocity, laser_sprite.clone());
+            let mut spawn_lazer = |x_offset: f32,
+                                   x_velocity: f32,
+                                   laser_velocity: f32,
+                                   laser_sprite: Handle<Image>| {
+                let mut laser = spawn_player_laser(
+                    &mut commands,
+                    &config,
+                    *palette,
+                    laser_sprite,
+                    Vec2::new(x + x_offset, y + 15.),
+                    Vec2::new(x_velocity, laser_velocity),
+                );
+                if **piercing_shot {
+                    laser.insert(Piercing::default());
+                }
+            };
+
+            if **spread_shot {
+                let x_velocity = laser_velocity * config.spread_shot_angle;
+                spawn_lazer(0., 0., laser_velocity, laser_sprite.clone());
+                spawn_lazer(x_offset, x_velocity, laser_velocity, laser_sprite.clone());
+                spawn_lazer(-x_offset, -x_velocity, laser_velocity, laser_sprite.clone());
+            } else {
+                spawn_lazer(x_offset, 0., laser_velocity, laser_sprite.clone());
+                spawn_lazer(-x_offset, 0., laser_velocity, laser_sprite.clone());
+            }
         }
     }
 }
+
+/// Spawns a laser that hits enemies, pointing its sprite along `velocity`.
+pub fn spawn_player_laser<'a>(
+    commands: &'a mut Commands,
+    config: &GameConfig,
+    palette: Palette,
+    sprite: Handle<Image>,
+    position: Vec2,
+    velocity: Vec2,
+) -> EntityCommands<'a> {
+    let scale = config.sprite_scale;
+    let mut laser = commands.spawn((
+        Sprite {
+            color: palette.player_laser(),
+            ..Sprite::from_image(sprite)
+        },
+        Transform {
+            translation: position.extend(1.0),
+            // point the sprite along its direction of travel
+            rotation: Quat::from_rotation_z(-velocity.x.atan2(velocity.y)),
+            scale: Vec3::new(scale, scale, 1.0),
+        },
+    ));
+    laser
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(SpriteSize(config.player_laser_size))
+        .insert(Movable { auto_despawn: true })
+        .insert(Lifetime::from_seconds(config.laser_lifetime))
+        .insert(Velocity {
+            x: velocity.x,
+            y: velocity.y,
+        });
+    laser
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::DespawnMargin;
+
+    fn player_laser_count(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world
+            .query_filtered::<(), (With<Laser>, With<FromPlayer>)>()
+            .iter(world)
+            .len()
+    }
+
+    fn set_state(app: &mut App, state: GameState) {
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+        app.update();
+    }
+
+    #[test]
+    fn no_lasers_after_the_player_dies() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .init_state::<GameState>()
+            .add_sub_state::<Pause>()
+            .add_event::<PlayerFired>()
+            .insert_resource(GameConfig::default())
+            .insert_resource(WinSize { w: 800.0, h: 800.0 })
+            .insert_resource(GameTextures::default())
+            .insert_resource(Difficulty::default())
+            .insert_resource(Palette::default())
+            .insert_resource(LaserUpgrage(false))
+            .insert_resource(SpreadShot(false))
+            .insert_resource(PiercingShot(false))
+            .insert_resource(Sandbox(false))
+            .insert_resource(WrapMode(false))
+            .insert_resource(Momentum(false))
+            .init_resource::<PlayerActions>()
+            .add_plugins(PlayerPlugin);
+
+        set_state(&mut app, GameState::Playing);
+        app.world_mut().resource_mut::<PlayerActions>().fire = true;
+        app.update();
+        // the ship fires while the run is live
+        assert!(player_laser_count(&mut app) > 0);
+
+        let world = app.world_mut();
+        let doomed: Vec<Entity> = world
+            .query_filtered::<Entity, Or<(With<Player>, With<Laser>)>>()
+            .iter(world)
+            .collect();
+        for entity in doomed {
+            world.despawn(entity);
+        }
+        set_state(&mut app, GameState::GameOver);
+
+        // a ready gun, so only the game state stands in the way
+        let cooldown = FireCooldown::from_world(app.world_mut());
+        app.insert_resource(cooldown);
+        for _ in 0..3 {
+            app.world_mut().resource_mut::<PlayerActions>().fire = true;
+            app.update();
+        }
+        assert_eq!(player_laser_count(&mut app), 0);
+
+        // even a ship left on the field stays silent outside a running game
+        app.world_mut()
+            .spawn((Player, Transform::from_xyz(0.0, -300.0, 10.0)));
+        app.world_mut().resource_mut::<PlayerActions>().fire = true;
+        app.update();
+        assert_eq!(player_laser_count(&mut app), 0);
+    }
+
+    #[test]
+    fn edge_leaves_room_for_the_scaled_wings() {
+        let win_size = WinSize { w: 800.0, h: 800.0 };
+        assert_eq!(
+            player_edge(&win_size, &GameConfig::default()),
+            800.0 / 2.0 - 144.0 / 2.0 * 0.5
+        );
+    }
+
+    fn hold_right_at_edge(momentum: bool) {
+        let win_size = WinSize { w: 800.0, h: 800.0 };
+        let config = GameConfig::default();
+        let edge = player_edge(&win_size, &config);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+                1.0 / 60.0,
+            )))
+            .insert_resource(PlayerActions {
+                move_x: 1.0,
+                ..default()
+            })
+            .insert_resource(win_size)
+            .insert_resource(config)
+            .insert_resource(WrapMode(false))
+            .insert_resource(Momentum(momentum))
+            .insert_resource(Difficulty::default())
+            .insert_resource(DespawnMargin(None))
+            .add_systems(Update, (player_input, movement, player_bounds).chain());
+        let player = app
+            .world_mut()
+            .spawn((
+                Player,
+                Transform::from_xyz(edge - 1.0, 0.0, 10.0),
+                Velocity { x: 0.0, y: 0.0 },
+                Movable {
+                    auto_despawn: false,
+                },
+            ))
+            .id();
+
+        let mut reached = false;
+        for _ in 0..60 {
+            app.update();
+            let x = app.world().get::<Transform>(player).unwrap().translation.x;
+            assert!(x <= edge, "ship at {x}, past the edge at {edge}");
+            reached |= x == edge;
+        }
+        assert!(reached, "ship never got to the edge");
+    }
+
+    #[test]
+    fn holding_right_stops_at_the_edge() {
+        hold_right_at_edge(false);
+    }
+
+    #[test]
+    fn holding_right_with_momentum_stops_at_the_edge() {
+        hold_right_at_edge(true);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{input::InputSystem, prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ContinuedRun, GameRng, GameState, RunReset,
+    attract::AttractMode,
+    components::MainMenu,
+    difficulty::Difficulty,
+    get_data_path,
+    input::{PlayerActions, read_mouse_actions},
+    rng_reseed,
+    save::{load_json, save_json},
+};
+
+#[derive(Serialize, Deserialize)]
+struct RecordedFrame {
+    /// Seconds the frame advanced the game by.
+    delta: f32,
+    actions: PlayerActions,
+}
+
+/// Every frame of the current run, saved to `replay.json` when it ends.
+///
+/// The tick model is one entry per rendered frame. Gameplay reads input only through
+/// `PlayerActions`, draws randomness only from `GameRng`, and moves only by
+/// `Time::delta`. Playback therefore reseeds the rng with the recorded seed, feeds each
+/// frame's actions back in, and forces each frame's recorded delta through
+/// `TimeUpdateStrategy`. That reproduces the run as long as the window size and menu
+/// options match the recording. Only the difficulty is restored automatically.
+#[derive(Resource, Default, Serialize, Deserialize)]
+struct InputLog {
+    seed: u64,
+    difficulty: Difficulty,
+    frames: Vec<RecordedFrame>,
+}
+
+#[derive(Resource, Deref)]
+struct ReplayPath(PathBuf);
+
+/// Frames being fed back in place of live input while a recording plays.
+#[derive(Resource, Default)]
+pub struct Playback {
+    frames: Vec<RecordedFrame>,
+    next: usize,
+    active: bool,
+}
+
+impl Playback {
+    /// Whether the current run is a recording playing back, up until the menu returns.
+    pub fn active(&self) -> bool {
+        self.active
+    }
+}
+
+pub struct ReplayPlugin;
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let replay_path = get_data_path("replay.json").unwrap_or_default();
+
+        app.init_resource::<InputLog>()
+            .init_resource::<Playback>()
+            .insert_resource(ReplayPath(replay_path))
+            .add_systems(
+                OnEnter(GameState::Playing),
+                replay_record_start.after(rng_reseed),
+            )
+            .add_systems(Update, replay_record.run_if(in_state(GameState::Playing)))
+            .add_systems(OnEnter(GameState::GameOver), replay_save)
+            .add_systems(OnEnter(GameState::MainMenu), playback_stop)
+            .add_systems(Update, playback_start.run_if(in_state(GameState::MainMenu)))
+            // stands in for the devices, so it overwrites whatever they read
+            .add_systems(
+                PreUpdate,
+                playback_feed
+                    .after(InputSystem)
+                    .after(read_mouse_actions)
+                    .run_if(|playback: Res<Playback>| playback.active),
+            );
+    }
+}
+
+/// Starts a fresh log, unless the run is being continued, which keeps recording into
+/// the log it had so the replay covers the whole run under its original seed.
+fn replay_record_start(
+    continued: Res<ContinuedRun>,
+    mut log: ResMut<InputLog>,
+    rng: Res<GameRng>,
+    difficulty: Res<Difficulty>,
+) {
+    if **continued {
+        return;
+    }
+    log.seed = rng.seed;
+    log.difficulty = *difficulty;
+    log.frames.clear();
+}
+
+fn replay_record(
+    time: Res<Time>,
+    actions: Res<PlayerActions>,
+    attract: Res<AttractMode>,
+    playback: Res<Playback>,
+    mut log: ResMut<InputLog>,
+) {
+    if **attract || playback.active {
+        return;
+    }
+    log.frames.push(RecordedFrame {
+        delta: time.delta_secs(),
+        actions: actions.clone(),
+    });
+}
+
+fn replay_save(
+    log: Res<InputLog>,
+    attract: Res<AttractMode>,
+    playback: Res<Playback>,
+    replay_path: Res<ReplayPath>,
+) {
+    if **attract || playback.active || log.frames.is_empty() {
+        return;
+    }
+    let _ = save_json(&replay_path, &*log);
+}
+
+/// Plays the last recorded run back from the main menu with [p].
+#[allow(clippy::too_many_arguments)]
+fn playback_start(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    replay_path: Res<ReplayPath>,
+    mut playback: ResMut<Playback>,
+    mut rng: ResMut<GameRng>,
+    mut difficulty: ResMut<Difficulty>,
+    mut next_state: ResMut<NextState<GameState>>,
+    main_menu_query: Query<Entity, With<MainMenu>>,
+    mut run: RunReset,
+) {
+    if !input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    let log: InputLog = load_json(&replay_path);
+    let Some(first) = log.frames.first() else {
+        return;
+    };
+
+    // the first frame of the run is the one after the state change
+    commands.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+        first.delta,
+    )));
+    rng.next_seed = Some(log.seed);
+    difficulty.set_if_neq(log.difficulty);
+    *playback = Playback {
+        frames: log.frames,
+        next: 0,
+        active: true,
+    };
+
+    for entity in &main_menu_query {
+        commands.entity(entity).despawn();
+    }
+    run.restart_run();
+    next_state.set(GameState::Playing);
+}
+
+/// Replaces this frame's input with the recorded one and lines up the recorded delta
+/// for the next frame. Live input takes over once the recording runs out.
+fn playback_feed(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut actions: ResMut<PlayerActions>,
+) {
+    let Some(frame) = playback.frames.get(playback.next) else {
+        return;
+    };
+    *actions = frame.actions.clone();
+    playback.next += 1;
+
+    let strategy = match playback.frames.get(playback.next) {
+        Some(next) => TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(next.delta)),
+        None => TimeUpdateStrategy::Automatic,
+    };
+    commands.insert_resource(strategy);
+}
+
+fn playback_stop(mut commands: Commands, mut playback: ResMut<Playback>) {
+    if playback.active {
+        *playback = Playback::default();
+        commands.insert_resource(TimeUpdateStrategy::Automatic);
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+
+use crate::{
+    GameState,
+    events::{PlayerFired, PlayerHit},
+    settings::Rumble,
+};
+
+/// Motor strength and seconds for each kind of rumble, from a light tap on firing to a
+/// long shake when the run ends.
+const FIRE_RUMBLE: (f32, f32) = (0.15, 0.05);
+const HIT_RUMBLE: (f32, f32) = (0.6, 0.25);
+const GAME_OVER_RUMBLE: (f32, f32) = (1.0, 0.8);
+
+pub struct RumblePlugin;
+impl Plugin for RumblePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (rumble_on_fire, rumble_on_hit).run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnEnter(GameState::GameOver), rumble_on_game_over);
+    }
+}
+
+/// Rumbles every connected gamepad, unless rumble is turned off in the settings.
+fn rumble(
+    requests: &mut EventWriter<GamepadRumbleRequest>,
+    setting: &Rumble,
+    gamepads: &Query<Entity, With<Gamepad>>,
+    (strength, seconds): (f32, f32),
+) {
+    if !**setting {
+        return;
+    }
+    for gamepad in gamepads {
+        requests.write(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity: GamepadRumbleIntensity {
+                strong_motor: strength,
+                weak_motor: strength,
+            },
+            duration: Duration::from_secs_f32(seconds),
+        });
+    }
+}
+
+fn rumble_on_fire(
+    mut fired: EventReader<PlayerFired>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+    setting: Res<Rumble>,
+    gamepads: Query<Entity, With<Gamepad>>,
+) {
+    // one tap per frame, however many shots went out
+    if fired.read().count() > 0 {
+        rumble(&mut requests, &setting, &gamepads, FIRE_RUMBLE);
+    }
+}
+
+fn rumble_on_hit(
+    mut player_hit: EventReader<PlayerHit>,
+    mut requests: EventWriter<GamepadRumbleRequest>,
+    setting: Res<Rumble>,
+    gamepads: Query<Entity, With<Gamepad>>,
+) {
+    if player_hit.read().count() > 0 {
+        rumble(&mut requests, &setting, &gamepads, HIT_RUMBLE);
+    }
+}
+
+fn rumble_on_game_over(
+    mut requests: EventWriter<GamepadRumbleRequest>,
+    setting: Res<Rumble>,
+    gamepads: Query<Entity, With<Gamepad>>,
+) {
+    rumble(&mut requests, &setting, &gamepads, GAME_OVER_RUMBLE);
+}
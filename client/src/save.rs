@@ -0,0 +1,59 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    log::warn,
+    tasks::{IoTaskPool, Task},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Reads a JSON file, falling back to the default value if it is missing or corrupt.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    fs::write(path, json)
+}
+
+/// Reads the high score. A missing file just means no score yet; anything unreadable or
+/// malformed is logged and treated as 0.
+pub fn load_high_score(path: &Path) -> u32 {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return 0,
+        Err(err) => {
+            warn!("could not read high score {:?}: {}", path, err);
+            return 0;
+        }
+    };
+    contents.trim().parse().unwrap_or_else(|err| {
+        warn!("ignoring malformed high score {:?}: {}", path, err);
+        0
+    })
+}
+
+/// Writes the high score on the IO task pool so a slow disk can't stall a frame. The
+/// returned task has to be kept around (or awaited) for the write to finish.
+pub fn save_high_score(path: PathBuf, high_score: u32) -> Task<()> {
+    IoTaskPool::get().spawn(async move {
+        if let Err(err) = fs::write(&path, high_score.to_string()) {
+            warn!("could not save high score {:?}: {}", path, err);
+        }
+    })
+}
+
+/// Deletes the saved high score. Nothing to delete is fine; any other failure is logged.
+pub fn clear_high_score(path: &Path) {
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => warn!("could not delete high score {:?}: {}", path, err),
+    }
+}
@@ -0,0 +1,374 @@
+use std::path::PathBuf;
+
+use bevy::{prelude::*, tasks::block_on};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState, HighScore, HighScorePath, PendingHighScoreWrite, centered_menu,
+    components::SettingsMenu,
+    get_config_path,
+    input::{KeyBindings, PlayerActions},
+    main_menu,
+    save::{clear_high_score, load_json, save_json},
+    stats::{Stats, StatsPath},
+};
+
+const VOLUME_STEP: f32 = 0.1;
+
+/// Volume levels from 0.0 to 1.0, persisted in `volume.json`. Sound effects and music
+/// play at their own level scaled by the master one.
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumeSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+}
+
+impl Default for VolumeSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            sfx: 1.0,
+            music: 0.6,
+        }
+    }
+}
+
+impl VolumeSettings {
+    const LABELS: [&str; 3] = ["master", "sfx", "music"];
+
+    fn level_mut(&mut self, row: usize) -> &mut f32 {
+        match row {
+            0 => &mut self.master,
+            1 => &mut self.sfx,
+            _ => &mut self.music,
+        }
+    }
+
+    fn levels(&self) -> [f32; 3] {
+        [self.master, self.sfx, self.music]
+    }
+}
+
+/// Tints for the sprites that matter most to tell apart, persisted in `palette.json`.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    /// The sprites' own colors.
+    #[default]
+    Standard,
+    /// Blue against orange, which stays distinct for red-green colorblindness.
+    BlueOrange,
+    /// White against yellow on the dark background, for low color sensitivity.
+    HighContrast,
+}
+
+impl Palette {
+    fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::BlueOrange => "blue/orange",
+            Palette::HighContrast => "high contrast",
+        }
+    }
+
+    fn cycled(&self, step: i32) -> Palette {
+        const ALL: [Palette; 3] = [
+            Palette::Standard,
+            Palette::BlueOrange,
+            Palette::HighContrast,
+        ];
+        let index = ALL.iter().position(|palette| palette == self).unwrap_or(0) as i32;
+        ALL[(index + step).rem_euclid(ALL.len() as i32) as usize]
+    }
+
+    pub fn player_laser(&self) -> Color {
+        match self {
+            Palette::Standard => Color::WHITE,
+            Palette::BlueOrange => Color::srgb(0.35, 0.7, 1.0),
+            Palette::HighContrast => Color::WHITE,
+        }
+    }
+
+    pub fn enemy_laser(&self) -> Color {
+        match self {
+            Palette::Standard => Color::WHITE,
+            Palette::BlueOrange => Color::srgb(1.0, 0.6, 0.0),
+            Palette::HighContrast => Color::srgb(1.0, 1.0, 0.0),
+        }
+    }
+
+    pub fn enemy(&self) -> Color {
+        match self {
+            Palette::Standard => Color::WHITE,
+            Palette::BlueOrange => Color::srgb(0.9, 0.4, 0.0),
+            Palette::HighContrast => Color::srgb(1.0, 0.85, 0.3),
+        }
+    }
+}
+
+#[derive(Resource, Deref)]
+struct VolumePath(PathBuf);
+
+#[derive(Resource, Deref)]
+struct PalettePath(PathBuf);
+
+/// Fire keeps shooting while held, limited by the fire cooldown, instead of once per
+/// press. Persisted in `rapid_fire.json`.
+#[derive(Resource, Deref, DerefMut, Default, Serialize, Deserialize)]
+pub struct RapidFire(bool);
+
+#[derive(Resource, Deref)]
+struct RapidFirePath(PathBuf);
+
+/// Marks the enemy in the ship's firing column. Persisted in `reticle.json`.
+#[derive(Resource, Deref, DerefMut, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reticle(pub bool);
+
+#[derive(Resource, Deref)]
+struct ReticlePath(PathBuf);
+
+/// Gamepads rumble on firing, hits and game over. Persisted in `rumble.json`.
+#[derive(Resource, Deref, DerefMut, Serialize, Deserialize)]
+pub struct Rumble(pub bool);
+
+impl Default for Rumble {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+#[derive(Resource, Deref)]
+struct RumblePath(PathBuf);
+
+/// Rows on the settings screen after the volume levels.
+const PALETTE_ROW: usize = VolumeSettings::LABELS.len();
+const RAPID_FIRE_ROW: usize = PALETTE_ROW + 1;
+const RETICLE_ROW: usize = RAPID_FIRE_ROW + 1;
+const RUMBLE_ROW: usize = RETICLE_ROW + 1;
+const RESET_HIGH_SCORE_ROW: usize = RUMBLE_ROW + 1;
+const SETTINGS_ROWS: usize = RESET_HIGH_SCORE_ROW + 1;
+
+/// Index of the highlighted settings row.
+#[derive(Resource, Deref, DerefMut, Default)]
+struct SettingsSelection(usize);
+
+/// Set while the high score reset waits for a yes or no.
+#[derive(Resource, Deref, DerefMut, Default, PartialEq)]
+struct ResetPending(bool);
+
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let volume_path = get_config_path("volume.json").unwrap_or_default();
+        let mut volume: VolumeSettings = load_json(&volume_path);
+        // hand-edited files may hold anything
+        for row in 0..VolumeSettings::LABELS.len() {
+            let level = volume.level_mut(row);
+            *level = level.clamp(0.0, 1.0);
+        }
+
+        let palette_path = get_config_path("palette.json").unwrap_or_default();
+        let palette: Palette = load_json(&palette_path);
+        let rapid_fire_path = get_config_path("rapid_fire.json").unwrap_or_default();
+        let rapid_fire: RapidFire = load_json(&rapid_fire_path);
+        let reticle_path = get_config_path("reticle.json").unwrap_or_default();
+        let reticle: Reticle = load_json(&reticle_path);
+        let rumble_path = get_config_path("rumble.json").unwrap_or_default();
+        let rumble: Rumble = load_json(&rumble_path);
+
+        app.insert_resource(volume)
+            .insert_resource(VolumePath(volume_path))
+            .insert_resource(palette)
+            .insert_resource(PalettePath(palette_path))
+            .insert_resource(rapid_fire)
+            .insert_resource(RapidFirePath(rapid_fire_path))
+            .insert_resource(reticle)
+            .insert_resource(ReticlePath(reticle_path))
+            .insert_resource(rumble)
+            .insert_resource(RumblePath(rumble_path))
+            .init_resource::<SettingsSelection>()
+            .init_resource::<ResetPending>()
+            .add_systems(OnEnter(GameState::Settings), settings_spawn)
+            .add_systems(
+                Update,
+                (
+                    settings_adjust.run_if(resource_equals(ResetPending(false))),
+                    settings_leave,
+                    settings_reset_high_score,
+                    settings_update,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Settings)),
+            );
+    }
+}
+
+fn settings_spawn(
+    mut commands: Commands,
+    mut selection: ResMut<SettingsSelection>,
+    mut reset_pending: ResMut<ResetPending>,
+) {
+    **selection = 0;
+    **reset_pending = false;
+    commands.spawn((centered_menu(String::new()), SettingsMenu));
+}
+
+/// Up and down pick a row, left and right change its value.
+#[allow(clippy::too_many_arguments)]
+fn settings_adjust(
+    input: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<SettingsSelection>,
+    mut volume: ResMut<VolumeSettings>,
+    volume_path: Res<VolumePath>,
+    mut palette: ResMut<Palette>,
+    palette_path: Res<PalettePath>,
+    mut rapid_fire: ResMut<RapidFire>,
+    rapid_fire_path: Res<RapidFirePath>,
+    mut reticle: ResMut<Reticle>,
+    reticle_path: Res<ReticlePath>,
+    mut rumble: ResMut<Rumble>,
+    rumble_path: Res<RumblePath>,
+) {
+    let rows = SETTINGS_ROWS;
+    if input.just_pressed(KeyCode::ArrowUp) {
+        **selection = (**selection + rows - 1) % rows;
+    }
+    if input.just_pressed(KeyCode::ArrowDown) {
+        **selection = (**selection + 1) % rows;
+    }
+
+    let step = if input.just_pressed(KeyCode::ArrowRight) {
+        1
+    } else if input.just_pressed(KeyCode::ArrowLeft) {
+        -1
+    } else {
+        return;
+    };
+
+    match **selection {
+        PALETTE_ROW => {
+            *palette = palette.cycled(step);
+            let _ = save_json(&palette_path, &*palette);
+        }
+        RAPID_FIRE_ROW => {
+            **rapid_fire = !**rapid_fire;
+            let _ = save_json(&rapid_fire_path, &*rapid_fire);
+        }
+        RETICLE_ROW => {
+            **reticle = !**reticle;
+            let _ = save_json(&reticle_path, &*reticle);
+        }
+        RUMBLE_ROW => {
+            **rumble = !**rumble;
+            let _ = save_json(&rumble_path, &*rumble);
+        }
+        RESET_HIGH_SCORE_ROW => {}
+        row => {
+            let step = step as f32 * VOLUME_STEP;
+            let level = volume.level_mut(row);
+            // round to the step so repeated presses land on 0.0 and 1.0 exactly
+            *level = ((*level + step) / VOLUME_STEP).round() * VOLUME_STEP;
+            *level = level.clamp(0.0, 1.0);
+            let _ = save_json(&volume_path, &*volume);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settings_update(
+    selection: Res<SettingsSelection>,
+    volume: Res<VolumeSettings>,
+    palette: Res<Palette>,
+    rapid_fire: Res<RapidFire>,
+    reticle: Res<Reticle>,
+    rumble: Res<Rumble>,
+    reset_pending: Res<ResetPending>,
+    menu_query: Query<&Children, With<SettingsMenu>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let rows: Vec<String> = VolumeSettings::LABELS
+        .iter()
+        .zip(volume.levels())
+        .map(|(label, level)| format!("{label}: {:.0}%", level * 100.0))
+        .chain([
+            format!("palette: {}", palette.name()),
+            format!("rapid fire: {}", if **rapid_fire { "on" } else { "off" }),
+            format!("target reticle: {}", if **reticle { "on" } else { "off" }),
+            format!("rumble: {}", if **rumble { "on" } else { "off" }),
+            if **reset_pending {
+                "reset high score and stats? yes [y] / no [n]".to_string()
+            } else {
+                "reset high score and stats [enter]".to_string()
+            },
+        ])
+        .enumerate()
+        .map(|(row, text)| {
+            let marker = if row == **selection { ">" } else { " " };
+            format!("{marker} {text}")
+        })
+        .collect();
+    let text = format!("Settings\n\n{}\n\nback [esc]", rows.join("\n"));
+    for children in &menu_query {
+        for child in children {
+            if let Ok(mut child_text) = text_query.get_mut(*child) {
+                child_text.set_if_neq(Text(text.clone()));
+            }
+        }
+    }
+}
+
+/// Confirm on the reset row asks before wiping the high score and the lifetime stats
+/// behind the leaderboard, instead of leaving.
+#[allow(clippy::too_many_arguments)]
+fn settings_reset_high_score(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Res<PlayerActions>,
+    selection: Res<SettingsSelection>,
+    mut reset_pending: ResMut<ResetPending>,
+    mut high_score: ResMut<HighScore>,
+    high_score_path: Res<HighScorePath>,
+    mut pending_write: ResMut<PendingHighScoreWrite>,
+    mut stats: ResMut<Stats>,
+    stats_path: Res<StatsPath>,
+) {
+    if !**reset_pending {
+        **reset_pending = **selection == RESET_HIGH_SCORE_ROW && actions.confirm;
+        return;
+    }
+    if keyboard.just_pressed(KeyCode::KeyY) {
+        **high_score = 0;
+        // a write still in flight would put the old score back
+        if let Some(write) = pending_write.take() {
+            block_on(write);
+        }
+        clear_high_score(&high_score_path);
+        *stats = Stats::default();
+        let _ = save_json(&stats_path, &*stats);
+        **reset_pending = false;
+    } else if keyboard.just_pressed(KeyCode::KeyN) || actions.pause {
+        **reset_pending = false;
+    }
+}
+
+fn settings_leave(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    bindings: Res<KeyBindings>,
+    selection: Res<SettingsSelection>,
+    reset_pending: Res<ResetPending>,
+    mut next_state: ResMut<NextState<GameState>>,
+    query: Query<Entity, With<SettingsMenu>>,
+) {
+    // the reset row handles confirm itself, and esc only backs out of its question
+    let confirm = actions.confirm && **selection != RESET_HIGH_SCORE_ROW;
+    if **reset_pending || (!confirm && !actions.pause) {
+        return;
+    }
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn(main_menu(&bindings));
+    next_state.set(GameState::MainMenu);
+}
@@ -0,0 +1,98 @@
+use bevy::{
+    math::bounding::{Aabb2d, IntersectsVolume},
+    prelude::*,
+};
+
+use crate::{
+    GameState, Lives,
+    components::{Movable, Player, Shield, ShieldPickup, SpriteSize, Velocity},
+    config::GameConfig,
+    difficulty::Difficulty,
+    mutators::Mutators,
+    pause::Pause,
+};
+
+const SHIELD_PICKUP_SIZE: f32 = 20.0;
+const SHIELD_PICKUP_COLOR: Color = Color::srgb(0.3, 1.0, 0.6);
+const SHIELD_TINT: Color = Color::srgb(0.5, 1.0, 0.8);
+
+pub struct ShieldPlugin;
+impl Plugin for ShieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), shields_reset)
+            .add_systems(
+                Update,
+                (shield_pickup, shield_tint).run_if(in_state(Pause::Running)),
+            );
+    }
+}
+
+/// Drops a pickup where a shield-carrying enemy went down.
+pub fn spawn_shield_pickup(commands: &mut Commands, position: Vec3) {
+    commands.spawn((
+        Sprite::from_color(SHIELD_PICKUP_COLOR, Vec2::splat(SHIELD_PICKUP_SIZE)),
+        Transform::from_xyz(position.x, position.y, 5.0),
+        SpriteSize(Vec2::splat(SHIELD_PICKUP_SIZE)),
+        Velocity { x: 0.0, y: -0.2 },
+        Movable { auto_despawn: true },
+        ShieldPickup,
+    ));
+}
+
+fn shields_reset(
+    mut commands: Commands,
+    pickup_query: Query<Entity, With<ShieldPickup>>,
+    player_query: Query<Entity, With<Shield>>,
+) {
+    for entity in &pickup_query {
+        commands.entity(entity).despawn();
+    }
+    for entity in &player_query {
+        commands.entity(entity).remove::<Shield>();
+    }
+}
+
+/// Gives back a life if one was lost this run, otherwise shields the ship.
+fn shield_pickup(
+    mut commands: Commands,
+    mut lives: ResMut<Lives>,
+    config: Res<GameConfig>,
+    difficulty: Res<Difficulty>,
+    mutators: Res<Mutators>,
+    player_query: Query<(Entity, &Transform, &SpriteSize), With<Player>>,
+    pickup_query: Query<(Entity, &Transform, &SpriteSize), With<ShieldPickup>>,
+) {
+    let Ok((player_entity, player_tf, player_size)) = player_query.single() else {
+        return;
+    };
+    let player_box = Aabb2d::new(
+        player_tf.translation.truncate(),
+        player_size.half_extents(player_tf.scale.truncate()),
+    );
+    let starting_lives = difficulty.starting_lives(mutators.starting_lives(config.player_lives));
+
+    for (entity, pickup_tf, pickup_size) in &pickup_query {
+        let pickup_box = Aabb2d::new(
+            pickup_tf.translation.truncate(),
+            pickup_size.half_extents(pickup_tf.scale.truncate()),
+        );
+        if !player_box.intersects(&pickup_box) {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        if **lives < starting_lives {
+            **lives += 1;
+        } else {
+            commands.entity(player_entity).insert(Shield);
+        }
+    }
+}
+
+fn shield_tint(mut player_query: Query<(&mut Sprite, Has<Shield>), With<Player>>) {
+    for (mut sprite, shielded) in &mut player_query {
+        let color = if shielded { SHIELD_TINT } else { Color::WHITE };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameState, Sandbox,
+    components::{Enemy, FromEnemy, Laser, Player, SlowField, Slowed},
+    input::PlayerActions,
+    pause::Pause,
+};
+
+const SLOW_FIELD_CHARGES: u32 = 3;
+const SLOW_FIELD_RADIUS: f32 = 150.0;
+const SLOW_FIELD_SECONDS: f32 = 4.0;
+
+/// Slow fields the player can still deploy this run.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SlowFieldCharges(pub u32);
+
+pub struct SlowFieldPlugin;
+impl Plugin for SlowFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SlowFieldCharges(SLOW_FIELD_CHARGES))
+            .add_systems(OnEnter(GameState::Playing), slow_field_reset)
+            .add_systems(Update, slow_field_deploy.run_if(in_state(Pause::Running)))
+            .add_systems(Update, slow_field_apply);
+    }
+}
+
+fn slow_field_reset(mut charges: ResMut<SlowFieldCharges>) {
+    **charges = SLOW_FIELD_CHARGES;
+}
+
+fn slow_field_deploy(
+    mut commands: Commands,
+    actions: Res<PlayerActions>,
+    sandbox: Res<Sandbox>,
+    mut charges: ResMut<SlowFieldCharges>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    if !actions.slow_field || (**charges == 0 && !**sandbox) {
+        return;
+    }
+    if !**sandbox {
+        **charges -= 1;
+    }
+
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(SLOW_FIELD_RADIUS))),
+        MeshMaterial2d(materials.add(Color::srgba(0.3, 0.6, 1.0, 0.15))),
+        Transform::from_xyz(player_tf.translation.x, player_tf.translation.y, 2.0),
+        SlowField {
+            radius: SLOW_FIELD_RADIUS,
+            timer: Timer::from_seconds(SLOW_FIELD_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+#[allow(clippy::type_complexity)]
+fn slow_field_apply(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut field_query: Query<(Entity, &mut SlowField, &Transform)>,
+    target_query: Query<
+        (Entity, &Transform, Has<Slowed>),
+        Or<(With<Enemy>, (With<Laser>, With<FromEnemy>))>,
+    >,
+) {
+    for (entity, mut field, _) in &mut field_query {
+        if field.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, target_tf, slowed) in &target_query {
+        let target_pos = target_tf.translation.truncate();
+        let inside = field_query.iter().any(|(_, field, field_tf)| {
+            !field.timer.finished()
+                && field_tf.translation.truncate().distance(target_pos) <= field.radius
+        });
+
+        if inside && !slowed {
+            commands.entity(entity).insert(Slowed);
+        } else if !inside && slowed {
+            commands.entity(entity).remove::<Slowed>();
+        }
+    }
+}
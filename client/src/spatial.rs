@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use bevy::{math::bounding::Aabb2d, prelude::*};
+
+/// Side of a grid cell in world units, around the size of the larger sprites.
+pub const GRID_CELL_SIZE: f32 = 128.0;
+
+/// Broad phase for collisions: entities are bucketed into every cell their box
+/// overlaps, so a lookup only returns entities that share a cell with the query box
+/// instead of everything on screen.
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Builds a grid from `(entity, box)` pairs.
+    pub fn from_boxes(cell_size: f32, boxes: impl IntoIterator<Item = (Entity, Aabb2d)>) -> Self {
+        let mut grid = Self::new(cell_size);
+        for (entity, aabb) in boxes {
+            grid.insert(entity, &aabb);
+        }
+        grid
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    fn cells_overlapping(&self, aabb: &Aabb2d) -> impl Iterator<Item = IVec2> + use<> {
+        let min = self.cell(aabb.min);
+        let max = self.cell(aabb.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    pub fn insert(&mut self, entity: Entity, aabb: &Aabb2d) {
+        for cell in self.cells_overlapping(aabb) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+    }
+
+    /// Entities sharing at least one cell with `aabb`, each listed once and in a
+    /// stable order. Callers still run the exact intersection test on them.
+    pub fn candidates(&self, aabb: &Aabb2d) -> Vec<Entity> {
+        let mut found: Vec<Entity> = self
+            .cells_overlapping(aabb)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::bounding::IntersectsVolume;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn aabb(center: Vec2, half_size: f32) -> Aabb2d {
+        Aabb2d::new(center, Vec2::splat(half_size))
+    }
+
+    #[test]
+    fn far_cells_are_left_out() {
+        let near = Entity::from_raw(1);
+        let far = Entity::from_raw(2);
+        let grid = SpatialGrid::from_boxes(
+            GRID_CELL_SIZE,
+            [
+                (near, aabb(Vec2::new(10.0, 10.0), 5.0)),
+                (far, aabb(Vec2::new(1000.0, -1000.0), 5.0)),
+            ],
+        );
+        assert_eq!(
+            grid.candidates(&aabb(Vec2::new(20.0, 20.0), 5.0)),
+            vec![near]
+        );
+    }
+
+    #[test]
+    fn boxes_straddling_a_cell_edge_are_found_from_either_side() {
+        let straddling = Entity::from_raw(1);
+        // spans the cells either side of both axes through the origin
+        let grid = SpatialGrid::from_boxes(GRID_CELL_SIZE, [(straddling, aabb(Vec2::ZERO, 10.0))]);
+        for corner in [
+            Vec2::new(-8.0, -8.0),
+            Vec2::new(8.0, -8.0),
+            Vec2::new(-8.0, 8.0),
+            Vec2::new(8.0, 8.0),
+        ] {
+            assert_eq!(grid.candidates(&aabb(corner, 1.0)), vec![straddling]);
+        }
+    }
+
+    #[test]
+    fn negative_coordinates_round_down_to_their_cell() {
+        let left = Entity::from_raw(1);
+        let edge = Entity::from_raw(2);
+        let grid = SpatialGrid::from_boxes(
+            GRID_CELL_SIZE,
+            [
+                // wholly inside cell (-1, 0); truncating would put it in cell (0, 0)
+                (left, aabb(Vec2::new(-64.0, 64.0), 10.0)),
+                // straddles the edge between cells -2 and -1
+                (edge, aabb(Vec2::new(-GRID_CELL_SIZE, -300.0), 10.0)),
+            ],
+        );
+        assert!(
+            grid.candidates(&aabb(Vec2::new(64.0, 64.0), 10.0))
+                .is_empty()
+        );
+        assert_eq!(
+            grid.candidates(&aabb(Vec2::new(-40.0, 40.0), 1.0)),
+            vec![left]
+        );
+        for x in [-GRID_CELL_SIZE - 5.0, -GRID_CELL_SIZE + 5.0] {
+            assert_eq!(
+                grid.candidates(&aabb(Vec2::new(x, -300.0), 1.0)),
+                vec![edge]
+            );
+        }
+    }
+
+    #[test]
+    fn candidates_are_listed_once() {
+        let entity = Entity::from_raw(1);
+        let grid = SpatialGrid::from_boxes(GRID_CELL_SIZE, [(entity, aabb(Vec2::ZERO, 50.0))]);
+        assert_eq!(grid.candidates(&aabb(Vec2::ZERO, 50.0)), vec![entity]);
+    }
+
+    #[test]
+    fn large_layouts_need_far_fewer_tests_than_every_pair() {
+        const LASERS: u32 = 300;
+        const ENEMIES: u32 = 300;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut random_box = |half_size| {
+            aabb(
+                Vec2::new(
+                    rng.random_range(-2000.0..2000.0),
+                    rng.random_range(-2000.0..2000.0),
+                ),
+                half_size,
+            )
+        };
+        let enemies: Vec<(Entity, Aabb2d)> = (0..ENEMIES)
+            .map(|index| (Entity::from_raw(index), random_box(36.0)))
+            .collect();
+        let lasers: Vec<Aabb2d> = (0..LASERS).map(|_| random_box(14.0)).collect();
+        let grid = SpatialGrid::from_boxes(GRID_CELL_SIZE, enemies.iter().copied());
+
+        let mut tests = 0;
+        for laser in &lasers {
+            let candidates = grid.candidates(laser);
+            tests += candidates.len() as u32;
+            // the broad phase must never drop a real hit
+            for (entity, enemy) in &enemies {
+                if enemy.intersects(laser) {
+                    assert!(candidates.contains(entity));
+                }
+            }
+        }
+        assert!(
+            tests * 20 < LASERS * ENEMIES,
+            "{tests} tests against {} pairs",
+            LASERS * ENEMIES
+        );
+    }
+}
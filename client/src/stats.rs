@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    GameState, Sandbox, Score,
+    attract::AttractMode,
+    combo::ComboState,
+    events::EnemyKilled,
+    get_data_path,
+    replay::Playback,
+    save::{load_json, save_json},
+};
+
+/// Lifetime statistics persisted across runs.
+#[derive(Resource, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games_played: u64,
+    pub enemies_destroyed: u64,
+    pub total_score: u64,
+}
+
+/// Figures for the current run, shown on the results screen.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub enemies_destroyed: u32,
+    pub longest_combo: u32,
+    pub new_high_score: bool,
+}
+
+#[derive(Resource, Deref)]
+pub(crate) struct StatsPath(PathBuf);
+
+pub struct StatsPlugin;
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        let stats_path = get_data_path("stats.json").unwrap_or_default();
+        let stats: Stats = load_json(&stats_path);
+
+        app.insert_resource(stats)
+            .insert_resource(StatsPath(stats_path))
+            .init_resource::<RunStats>()
+            .add_systems(Update, count_kills)
+            .add_systems(Update, track_run_stats.run_if(in_state(GameState::Playing)))
+            // recorded once the run is over for good, not at a death that gets continued
+            .add_systems(
+                OnTransition {
+                    exited: GameState::GameOver,
+                    entered: GameState::MainMenu,
+                },
+                (record_game, save_stats).chain(),
+            );
+    }
+}
+
+fn count_kills(
+    mut stats: ResMut<Stats>,
+    sandbox: Res<Sandbox>,
+    attract: Res<AttractMode>,
+    playback: Res<Playback>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+) {
+    let kills = enemy_killed.read().count() as u64;
+    if !**sandbox && !**attract && !playback.active() {
+        stats.enemies_destroyed += kills;
+    }
+}
+
+fn track_run_stats(
+    mut run_stats: ResMut<RunStats>,
+    combo: Res<ComboState>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+) {
+    run_stats.enemies_destroyed += enemy_killed.read().count() as u32;
+    run_stats.longest_combo = run_stats.longest_combo.max(combo.count);
+}
+
+fn record_game(
+    mut stats: ResMut<Stats>,
+    score: Res<Score>,
+    sandbox: Res<Sandbox>,
+    playback: Res<Playback>,
+) {
+    // a replay already counted when it was played
+    if !**sandbox && !playback.active() {
+        stats.games_played += 1;
+        stats.total_score += **score as u64;
+    }
+}
+
+fn save_stats(stats: Res<Stats>, stats_path: Res<StatsPath>) {
+    let _ = save_json(&stats_path, &*stats);
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameState,
+    components::{Enemy, Exposed, Player},
+    settings::Reticle,
+};
+
+const RETICLE_RADIUS: f32 = 28.0;
+const RETICLE_COLOR: Color = Color::srgba(1.0, 0.9, 0.2, 0.8);
+
+pub struct TargetingPlugin;
+impl Plugin for TargetingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            reticle_draw.run_if(in_state(GameState::Playing).and(resource_equals(Reticle(true)))),
+        );
+    }
+}
+
+/// Picks the enemy closest to `from`, breaking distance ties on the lowest entity id
+/// so every feature targets the same enemy.
+pub fn nearest_enemy(
+    from: Vec2,
+    enemies: impl IntoIterator<Item = (Entity, Vec2)>,
+) -> Option<Entity> {
+    enemies
+        .into_iter()
+        .min_by(|(a_entity, a_pos), (b_entity, b_pos)| {
+            from.distance_squared(*a_pos)
+                .total_cmp(&from.distance_squared(*b_pos))
+                .then_with(|| a_entity.cmp(b_entity))
+        })
+        .map(|(entity, _)| entity)
+}
+
+/// Picks the enemy horizontally closest to `x`, the one a straight shot from there is
+/// most likely to hit. Ties go to the lowest entity id like `nearest_enemy`.
+pub fn nearest_in_column(
+    x: f32,
+    enemies: impl IntoIterator<Item = (Entity, Vec2)>,
+) -> Option<Entity> {
+    enemies
+        .into_iter()
+        .min_by(|(a_entity, a_pos), (b_entity, b_pos)| {
+            (a_pos.x - x)
+                .abs()
+                .total_cmp(&(b_pos.x - x).abs())
+                .then_with(|| a_entity.cmp(b_entity))
+        })
+        .map(|(entity, _)| entity)
+}
+
+/// Rings the enemy in the player's firing column. Draws nothing without a player or
+/// with no enemy that can be hit.
+fn reticle_draw(
+    mut gizmos: Gizmos,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(Entity, &Transform), (With<Enemy>, Exposed)>,
+) {
+    let Ok(player_tf) = player_query.single() else {
+        return;
+    };
+    let target = nearest_in_column(
+        player_tf.translation.x,
+        enemy_query
+            .iter()
+            .map(|(entity, tf)| (entity, tf.translation.truncate())),
+    );
+    let Some((_, target_tf)) = target.and_then(|entity| enemy_query.get(entity).ok()) else {
+        return;
+    };
+
+    let center = target_tf.translation.truncate();
+    gizmos.circle_2d(
+        Isometry2d::from_translation(center),
+        RETICLE_RADIUS,
+        RETICLE_COLOR,
+    );
+    for direction in [Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y] {
+        gizmos.line_2d(
+            center + direction * RETICLE_RADIUS * 0.6,
+            center + direction * RETICLE_RADIUS * 1.3,
+            RETICLE_COLOR,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_enemies_means_no_target() {
+        assert_eq!(nearest_enemy(Vec2::ZERO, []), None);
+        assert_eq!(nearest_in_column(0.0, []), None);
+    }
+
+    #[test]
+    fn closest_enemy_wins() {
+        let near = Entity::from_raw(7);
+        let far = Entity::from_raw(1);
+        let enemies = [(far, Vec2::new(0.0, 300.0)), (near, Vec2::new(50.0, 100.0))];
+        assert_eq!(nearest_enemy(Vec2::ZERO, enemies), Some(near));
+
+        let enemies = [
+            (far, Vec2::new(-80.0, 50.0)),
+            (near, Vec2::new(20.0, 400.0)),
+        ];
+        assert_eq!(nearest_in_column(0.0, enemies), Some(near));
+    }
+
+    #[test]
+    fn ties_go_to_the_lowest_entity() {
+        let low = Entity::from_raw(2);
+        let high = Entity::from_raw(9);
+        let enemies = [(high, Vec2::new(100.0, 0.0)), (low, Vec2::new(-100.0, 0.0))];
+        assert_eq!(nearest_enemy(Vec2::ZERO, enemies), Some(low));
+
+        let enemies = [
+            (high, Vec2::new(30.0, 100.0)),
+            (low, Vec2::new(-30.0, 300.0)),
+        ];
+        assert_eq!(nearest_in_column(0.0, enemies), Some(low));
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+
+use crate::{
+    GameState,
+    attract::AttractMode,
+    components::TutorialUI,
+    events::EnemyKilled,
+    get_config_path,
+    input::PlayerActions,
+    pause::{Pause, pause_toggle},
+    replay::Playback,
+};
+
+/// Prompt shown for each step, finished by doing what it asks.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tutorial {
+    #[default]
+    Off,
+    Move,
+    Fire,
+    Goal,
+}
+
+impl Tutorial {
+    fn prompt(&self) -> &'static str {
+        match self {
+            Tutorial::Off => "",
+            Tutorial::Move => "Move left and right with [a] and [d]",
+            Tutorial::Fire => "Shoot with [up-arrow]",
+            Tutorial::Goal => "Shoot down the invaders before they reach you",
+        }
+    }
+}
+
+/// Written once the tutorial is finished or skipped, so it only shows on the first run.
+#[derive(Resource, Deref)]
+struct TutorialDonePath(PathBuf);
+
+pub struct TutorialPlugin;
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        let done_path = get_config_path("tutorial_done").unwrap_or_default();
+
+        app.init_resource::<Tutorial>()
+            .insert_resource(TutorialDonePath(done_path))
+            .add_systems(OnEnter(GameState::Playing), tutorial_start)
+            .add_systems(OnExit(GameState::Playing), tutorial_stop)
+            .add_systems(
+                Update,
+                // esc dismisses the tutorial rather than pausing
+                (tutorial_advance.before(pause_toggle), tutorial_update)
+                    .chain()
+                    .run_if(in_state(Pause::Running))
+                    .run_if(not(resource_equals(Tutorial::Off))),
+            );
+    }
+}
+
+fn tutorial_start(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    done_path: Res<TutorialDonePath>,
+    attract: Res<AttractMode>,
+    playback: Res<Playback>,
+    ui_query: Query<Entity, With<TutorialUI>>,
+) {
+    // a restart from the pause menu enters `Playing` again without leaving it
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+    *tutorial = Tutorial::Off;
+    if done_path.exists() || **attract || playback.active() {
+        return;
+    }
+
+    *tutorial = Tutorial::Move;
+    commands.spawn((
+        Text::default(),
+        TextFont::from_font_size(24.0),
+        TextLayout::new_with_justify(JustifyText::Center),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(30.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TutorialUI,
+    ));
+}
+
+/// A run that ends early shows the tutorial again next time.
+fn tutorial_stop(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    ui_query: Query<Entity, With<TutorialUI>>,
+) {
+    *tutorial = Tutorial::Off;
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn tutorial_advance(
+    mut tutorial: ResMut<Tutorial>,
+    mut actions: ResMut<PlayerActions>,
+    done_path: Res<TutorialDonePath>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+) {
+    let killed = enemy_killed.read().count() > 0;
+    let skipped = actions.pause;
+    actions.pause = false;
+
+    *tutorial = match *tutorial {
+        _ if skipped => Tutorial::Off,
+        Tutorial::Move if actions.move_x != 0.0 || actions.aim_x.is_some() => Tutorial::Fire,
+        Tutorial::Fire if actions.fire => Tutorial::Goal,
+        Tutorial::Goal if killed => Tutorial::Off,
+        step => step,
+    };
+    if *tutorial == Tutorial::Off
+        && let Err(err) = fs::write(&**done_path, "")
+    {
+        warn!(
+            "could not save tutorial progress {:?}: {}",
+            **done_path, err
+        );
+    }
+}
+
+fn tutorial_update(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    mut ui_query: Query<(Entity, &mut Text), With<TutorialUI>>,
+) {
+    for (entity, mut text) in &mut ui_query {
+        if *tutorial == Tutorial::Off {
+            commands.entity(entity).despawn();
+        } else {
+            text.set_if_neq(Text(format!("{}\n\nskip [esc]", tutorial.prompt())));
+        }
+    }
+}
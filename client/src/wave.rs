@@ -0,0 +1,391 @@
+use std::{collections::VecDeque, fs, path::Path};
+
+use bevy::{prelude::*, time::Stopwatch};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    ContinuedRun, GameRng, GameState, GameTextures, Score, WinSize,
+    components::{Enemy, Toast},
+    config::GameConfig,
+    difficulty::Difficulty,
+    enemy::{EnemyKind, spawn_enemy},
+    events::{EnemyKilled, WaveStarted},
+    get_config_path,
+    settings::Palette,
+};
+
+/// Kills needed to advance a procedural wave.
+const WAVE_KILLS: u32 = 10;
+/// Bonus for clearing a wave instantly, shrinking to nothing at `WAVE_BONUS_PAR_SECONDS`.
+const WAVE_BONUS_MAX: u32 = 50;
+const WAVE_BONUS_PAR_SECONDS: f32 = 60.0;
+/// Pause in spawning after a wave is cleared.
+const WAVE_BREATHER_SECONDS: f32 = 3.0;
+
+#[derive(Resource)]
+pub struct Wave {
+    pub number: u32,
+    pub kills: u32,
+    /// Time spent on the current wave, for the clear bonus.
+    pub elapsed: Stopwatch,
+    /// Runs after a clear; nothing spawns until it finishes.
+    pub breather: Timer,
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        let mut breather = Timer::from_seconds(WAVE_BREATHER_SECONDS, TimerMode::Once);
+        // no breather before the first wave
+        breather.tick(breather.remaining());
+        Self {
+            number: 1,
+            kills: 0,
+            elapsed: Stopwatch::new(),
+            breather,
+        }
+    }
+}
+
+impl Wave {
+    /// Points for clearing the current wave, more the faster it went.
+    fn clear_bonus(&self) -> u32 {
+        let left = 1.0 - self.elapsed.elapsed_secs() / WAVE_BONUS_PAR_SECONDS;
+        (WAVE_BONUS_MAX as f32 * left.max(0.0)).round() as u32
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnPattern {
+    /// Anywhere in the upper play area.
+    Random,
+    /// Evenly spaced across the top of the screen.
+    Row,
+}
+
+#[derive(Deserialize)]
+pub struct SpawnGroup {
+    pub kind: EnemyKind,
+    pub count: u32,
+    pub pattern: SpawnPattern,
+}
+
+#[derive(Deserialize)]
+pub struct WaveDefinition {
+    /// Seconds between two spawns of this wave.
+    pub spawn_interval: f32,
+    pub groups: Vec<SpawnGroup>,
+}
+
+/// Hand-authored waves loaded from `waves.json`; once they run out the game falls
+/// back to endless procedural waves.
+#[derive(Resource, Default, Deserialize)]
+pub struct WaveDefinitions {
+    pub waves: Vec<WaveDefinition>,
+}
+
+impl WaveDefinitions {
+    fn load(path: &Path) -> Self {
+        let Ok(json) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<WaveDefinitions>(&json) {
+            Ok(definitions) => match definitions.validate() {
+                Ok(()) => definitions,
+                Err(err) => {
+                    warn!("invalid wave definitions in {:?}: {}", path, err);
+                    Self::default()
+                }
+            },
+            Err(err) => {
+                warn!("could not parse wave definitions {:?}: {}", path, err);
+                Self::default()
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, wave) in self.waves.iter().enumerate() {
+            if wave.spawn_interval <= 0.0 {
+                return Err(format!(
+                    "wave {} has a non-positive spawn_interval",
+                    index + 1
+                ));
+            }
+            if wave.groups.iter().all(|group| group.count == 0) {
+                return Err(format!("wave {} spawns no enemies", index + 1));
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, wave: u32) -> Option<&WaveDefinition> {
+        self.waves.get(wave as usize - 1)
+    }
+}
+
+/// Pending spawns of the current scripted wave.
+#[derive(Resource)]
+struct WaveSpawner {
+    queue: VecDeque<(EnemyKind, SpawnPattern, u32, u32)>,
+    timer: Timer,
+}
+
+impl Default for WaveSpawner {
+    fn default() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl WaveSpawner {
+    fn load(&mut self, definition: &WaveDefinition) {
+        self.queue.clear();
+        for group in &definition.groups {
+            for index in 0..group.count {
+                self.queue
+                    .push_back((group.kind, group.pattern, index, group.count));
+            }
+        }
+        self.timer = Timer::from_seconds(definition.spawn_interval, TimerMode::Repeating);
+    }
+}
+
+/// Run condition: true when enemies should come from random spawning.
+pub fn procedural_waves(
+    state: Res<State<GameState>>,
+    wave: Res<Wave>,
+    definitions: Res<WaveDefinitions>,
+) -> bool {
+    *state.get() != GameState::Playing
+        || (definitions.get(wave.number).is_none() && wave.breather.finished())
+}
+
+pub struct WavePlugin;
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        let definitions_path = get_config_path("waves.json").unwrap_or_default();
+
+        app.init_resource::<Wave>()
+            .init_resource::<WaveSpawner>()
+            .insert_resource(WaveDefinitions::load(&definitions_path))
+            .add_systems(OnEnter(GameState::Playing), wave_start)
+            // the flush between the two lets the clear check see an enemy spawned this frame
+            .add_systems(
+                Update,
+                (wave_spawn, wave_progress)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+fn wave_start(
+    mut wave: ResMut<Wave>,
+    mut spawner: ResMut<WaveSpawner>,
+    definitions: Res<WaveDefinitions>,
+    continued: Res<ContinuedRun>,
+    mut wave_started: EventWriter<WaveStarted>,
+) {
+    // a continue restarts the wave it died on rather than going back to the first
+    if !**continued {
+        *wave = Wave::default();
+    }
+    wave.kills = 0;
+    wave.elapsed.reset();
+    let remaining = wave.breather.remaining();
+    wave.breather.tick(remaining);
+    spawner.queue.clear();
+    if let Some(definition) = definitions.get(wave.number) {
+        spawner.load(definition);
+    }
+    wave_started.write(WaveStarted(wave.number));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn wave_progress(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut wave: ResMut<Wave>,
+    mut score: ResMut<Score>,
+    mut spawner: ResMut<WaveSpawner>,
+    definitions: Res<WaveDefinitions>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut enemy_killed: EventReader<EnemyKilled>,
+    mut wave_started: EventWriter<WaveStarted>,
+) {
+    let kills = enemy_killed.read().count() as u32;
+    wave.breather.tick(time.delta());
+    if !wave.breather.finished() {
+        return;
+    }
+    wave.elapsed.tick(time.delta());
+
+    let cleared = if definitions.get(wave.number).is_some() {
+        // scripted waves end once everything has spawned and been dealt with
+        spawner.queue.is_empty() && enemy_query.is_empty()
+    } else {
+        wave.kills += kills;
+        wave.kills >= WAVE_KILLS
+    };
+
+    if cleared {
+        let bonus = wave.clear_bonus();
+        **score += bonus;
+        commands.spawn((
+            Text::new(format!("Wave Cleared! +{bonus}")),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+            Toast::default(),
+        ));
+
+        wave.number += 1;
+        wave.kills = 0;
+        wave.elapsed.reset();
+        wave.breather.reset();
+        if let Some(definition) = definitions.get(wave.number) {
+            spawner.load(definition);
+        }
+        wave_started.write(WaveStarted(wave.number));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn wave_spawn(
+    mut commands: Commands,
+    time: Res<Time>,
+    wave: Res<Wave>,
+    mut spawner: ResMut<WaveSpawner>,
+    game_textures: Res<GameTextures>,
+    config: Res<GameConfig>,
+    palette: Res<Palette>,
+    difficulty: Res<Difficulty>,
+    win_size: Res<WinSize>,
+    mut rng: ResMut<GameRng>,
+) {
+    if spawner.queue.is_empty()
+        || !wave.breather.finished()
+        || !spawner.timer.tick(time.delta()).just_finished()
+    {
+        return;
+    }
+    let Some((kind, pattern, index, count)) = spawner.queue.pop_front() else {
+        return;
+    };
+
+    let position = match pattern {
+        SpawnPattern::Random => {
+            let w_span = win_size.w / 2.0 - 100.0;
+            Vec2::new(
+                rng.random_range(-w_span..w_span),
+                rng.random_range(0.0..win_size.h / 2.0 - 100.0),
+            )
+        }
+        SpawnPattern::Row => {
+            let spacing = win_size.w / (count + 1) as f32;
+            Vec2::new(
+                -win_size.w / 2.0 + spacing * (index + 1) as f32,
+                win_size.h / 2.0 - 100.0,
+            )
+        }
+    };
+    spawn_enemy(
+        &mut commands,
+        &game_textures,
+        &config,
+        *palette,
+        *difficulty,
+        &mut rng,
+        kind,
+        position,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{state::app::StatesPlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    fn scripted_wave(count: u32) -> WaveDefinition {
+        WaveDefinition {
+            spawn_interval: 1.0,
+            groups: vec![SpawnGroup {
+                kind: EnemyKind::Basic,
+                count,
+                pattern: SpawnPattern::Row,
+            }],
+        }
+    }
+
+    #[test]
+    fn last_queued_enemy_spawning_does_not_clear_the_wave() {
+        let config = GameConfig::default();
+        let definitions = WaveDefinitions {
+            waves: vec![scripted_wave(2), scripted_wave(2)],
+        };
+        let mut spawner = WaveSpawner::default();
+        spawner.load(&definitions.waves[0]);
+        // the first enemy has already spawned and been shot down
+        spawner.queue.pop_front();
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin))
+            .insert_state(GameState::Playing)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )))
+            .add_event::<EnemyKilled>()
+            .add_event::<WaveStarted>()
+            .init_resource::<Wave>()
+            .insert_resource(spawner)
+            .insert_resource(definitions)
+            .insert_resource(Score(0))
+            .insert_resource(GameTextures::default())
+            .insert_resource(GameRng::new(&config))
+            .insert_resource(config)
+            .insert_resource(Palette::default())
+            .insert_resource(Difficulty::default())
+            .insert_resource(WinSize { w: 800.0, h: 600.0 })
+            // mirrors WavePlugin without loading waves.json from the config directory
+            .add_systems(
+                Update,
+                (wave_spawn, wave_progress)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+
+        let mut enemies = 0;
+        for _ in 0..8 {
+            app.update();
+            let world = app.world_mut();
+            enemies = world.query_filtered::<(), With<Enemy>>().iter(world).len();
+            if enemies > 0 {
+                break;
+            }
+        }
+        assert_eq!(enemies, 1, "the last queued enemy never spawned");
+        assert_eq!(app.world().resource::<Wave>().number, 1);
+        assert_eq!(**app.world().resource::<Score>(), 0);
+
+        let world = app.world_mut();
+        let enemy = world
+            .query_filtered::<Entity, With<Enemy>>()
+            .single(world)
+            .unwrap();
+        world.despawn(enemy);
+        app.update();
+        assert_eq!(app.world().resource::<Wave>().number, 2);
+        assert!(**app.world().resource::<Score>() > 0);
+    }
+}